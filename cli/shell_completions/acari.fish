@@ -19,17 +19,32 @@ complete -c acari -l no-cache -d "disable caching"
 
 # subcommands
 complete -f -c acari -n "__fish_use_subcommand" -a add -d "add time entry"
+complete -f -c acari -n "__fish_use_subcommand" -a alias -d "manage aliases"
+complete -f -c acari -n "__fish_use_subcommand" -a apply -d "apply timesheet file"
 complete -f -c acari -n "__fish_use_subcommand" -a init -d "initialize connection"
 complete -f -c acari -n "__fish_use_subcommand" -a check -d "check connection"
+complete -f -c acari -n "__fish_use_subcommand" -a config -d "show configuration"
+complete -f -c acari -n "__fish_use_subcommand" -a copy -d "copy time entries"
 complete -f -c acari -n "__fish_use_subcommand" -a clear-cache -d "Clear local cache"
 complete -f -c acari -n "__fish_use_subcommand" -a customers -d "list customers"
+complete -f -c acari -n "__fish_use_subcommand" -a delete -d "delete time entry"
+complete -f -c acari -n "__fish_use_subcommand" -a edit -d "edit time entry"
+complete -f -c acari -n "__fish_use_subcommand" -a edit-span -d "edit time entries of a date span in editor"
 complete -f -c acari -n "__fish_use_subcommand" -a entries -d "list time entries"
+complete -f -c acari -n "__fish_use_subcommand" -a export -d "export time entries"
+complete -f -c acari -n "__fish_use_subcommand" -a profile -d "manage profiles"
+complete -f -c acari -n "__fish_use_subcommand" -a profiles -d "list profiles"
 complete -f -c acari -n "__fish_use_subcommand" -a projects -d "list projects"
+complete -f -c acari -n "__fish_use_subcommand" -a recurring -d "manage recurring entries"
 complete -f -c acari -n "__fish_use_subcommand" -a services -d "list services"
 complete -f -c acari -n "__fish_use_subcommand" -a set -d "set time entry"
 complete -f -c acari -n "__fish_use_subcommand" -a start -d "start time tracking"
+complete -f -c acari -n "__fish_use_subcommand" -a summary -d "show totals of time entries"
 complete -f -c acari -n "__fish_use_subcommand" -a stop -d "stop time tracking"
 complete -f -c acari -n "__fish_use_subcommand" -a tracking -d "show current time tracking"
+complete -f -c acari -n "__fish_use_subcommand" -a tui -d "browse and edit time entries"
+complete -f -c acari -n "__fish_use_subcommand" -a warm-cache -d "Fill local cache"
+complete -f -c acari -n "__fish_use_subcommand" -a week -d "show time entries as grid"
 
 # add
 complete -f -c acari -n "__fish_seen_subcommand_from add"
@@ -37,25 +52,89 @@ complete -f -c acari -n "__fish_seen_subcommand_from add; and __fish_is_arg_n 2"
 complete -f -c acari -n "__fish_seen_subcommand_from add; and __fish_is_arg_n 3" -a "(acari (__profile_arg) -oflat projects (__fish_arg_n 2))" 
 complete -f -c acari -n "__fish_seen_subcommand_from add; and __fish_is_arg_n 4" -a "(acari (__profile_arg) -oflat services (__fish_arg_n 2) (__fish_arg_n 3))" 
 
+# alias
+complete -f -c acari -n "__fish_seen_subcommand_from alias; and __fish_is_arg_n 2" -a "add list remove"
+complete -f -c acari -n "__fish_seen_subcommand_from alias; and __fish_seen_subcommand_from remove; and __fish_is_arg_n 3" -a "(acari (__profile_arg) -oflat alias list | cut -f1 | string sub -s 2)"
+
+# apply
+complete -c acari -n "__fish_seen_subcommand_from apply" -l dry-run -d "only show changes"
+complete -f -c acari -n "__fish_seen_subcommand_from apply" -l span -a "today yesterday this-week last-week this-month last-month" -d "reconcile every day of span"
+complete -f -c acari -n "__fish_seen_subcommand_from apply" -l archived -d "include archived"
+
+# init
+complete -f -c acari -n "__fish_seen_subcommand_from init" -l backend -a "mite everhour" -d "backend to use"
+complete -f -c acari -n "__fish_seen_subcommand_from init" -l domain -d "domain of the account"
+complete -f -c acari -n "__fish_seen_subcommand_from init" -l token -d "api token"
+complete -f -c acari -n "__fish_seen_subcommand_from init" -l token-env -d "read api token from environment variable"
+complete -f -c acari -n "__fish_seen_subcommand_from init" -l token-command -d "read api token from command"
+complete -c acari -n "__fish_seen_subcommand_from init" -l token-file -d "read api token from file"
+complete -f -c acari -n "__fish_seen_subcommand_from init" -s y -l yes -d "do not ask any questions"
+complete -f -c acari -n "__fish_seen_subcommand_from init" -l skip-check -d "do not check credentials"
+
 # check
 complete -f -c acari -n "__fish_seen_subcommand_from check"
 
 # check
 complete -f -c acari -n "__fish_seen_subcommand_from clear-cache"
 
+# config
+complete -f -c acari -n "__fish_seen_subcommand_from config; and __fish_is_arg_n 2" -a "show"
+complete -f -c acari -n "__fish_seen_subcommand_from config" -l resolved -d "show effective settings"
+
+# copy
+complete -f -c acari -n "__fish_seen_subcommand_from copy"
+complete -f -c acari -n "__fish_seen_subcommand_from copy; and __fish_is_arg_n 2" -a "today yesterday this-week last-week this-month last-month"
+complete -f -c acari -n "__fish_seen_subcommand_from copy; and __fish_is_arg_n 3" -a "today yesterday this-week last-week this-month last-month"
+complete -f -c acari -n "__fish_seen_subcommand_from copy" -l shift -d "keep distribution over days"
+complete -f -c acari -n "__fish_seen_subcommand_from copy" -l no-notes -d "do not copy notes"
+complete -f -c acari -n "__fish_seen_subcommand_from copy" -l no-time -d "do not copy time"
+complete -f -c acari -n "__fish_seen_subcommand_from copy" -l skip-existing -d "skip days with entries"
+complete -f -c acari -n "__fish_seen_subcommand_from copy" -l dry-run -d "only show what would be copied"
+
 # customers
 complete -f -c acari -n "__fish_seen_subcommand_from customers"
 
+# delete
+complete -f -c acari -n "__fish_seen_subcommand_from delete"
+complete -f -c acari -n "__fish_seen_subcommand_from delete" -s y -l yes -d "do not ask for confirmation"
+
+# edit
+complete -f -c acari -n "__fish_seen_subcommand_from edit"
+complete -f -c acari -n "__fish_seen_subcommand_from edit" -s t -l time -d "new time"
+complete -f -c acari -n "__fish_seen_subcommand_from edit" -s n -l note -d "new note"
+
+# edit-span
+complete -f -c acari -n "__fish_seen_subcommand_from edit-span"
+complete -f -c acari -n "__fish_seen_subcommand_from edit-span; and __fish_is_arg_n 2" -a "today yesterday this-week last-week this-month last-month"
+complete -f -c acari -n "__fish_seen_subcommand_from edit-span" -s y -l yes -d "apply without confirmation"
+
 # entries
 complete -f -c acari -n "__fish_seen_subcommand_from entries"
 complete -f -c acari -n "__fish_seen_subcommand_from entries; and __fish_is_arg_n 2" -a "today yesterday this-week last-week this-month last-month (date +%Y-%m-%d)" 
 
+# export
+complete -f -c acari -n "__fish_seen_subcommand_from export"
+complete -f -c acari -n "__fish_seen_subcommand_from export; and __fish_is_arg_n 2" -a "today yesterday this-week last-week this-month last-month"
+complete -f -c acari -n "__fish_seen_subcommand_from export" -l timesheet -d "export as timesheet"
+
+# profile
+complete -f -c acari -n "__fish_seen_subcommand_from profile; and __fish_is_arg_n 2" -a "add remove rename default show"
+complete -f -c acari -n "__fish_seen_subcommand_from profile; and __fish_is_arg_n 3" -a "(acari profiles)"
+complete -f -c acari -n "__fish_seen_subcommand_from profile; and __fish_seen_subcommand_from add" -l from-default -d "move top level settings into the profile"
+
 # projects
 complete -f -c acari -n "__fish_seen_subcommand_from projects"
 complete -f -c acari -n "__fish_seen_subcommand_from projects; and __fish_is_arg_n 2" -a "(acari (__profile_arg) -oflat customers)" 
 
+# recurring
+complete -f -c acari -n "__fish_seen_subcommand_from recurring; and __fish_is_arg_n 2" -a "apply list"
+complete -f -c acari -n "__fish_seen_subcommand_from recurring; and __fish_seen_subcommand_from apply; and __fish_is_arg_n 3" -a "today yesterday this-week last-week this-month last-month"
+complete -f -c acari -n "__fish_seen_subcommand_from recurring; and __fish_seen_subcommand_from apply" -l dry-run -d "only show what would be created"
+complete -f -c acari -n "__fish_seen_subcommand_from recurring; and __fish_seen_subcommand_from apply" -l archived -d "include archived"
+
 # services
 complete -f -c acari -n "__fish_seen_subcommand_from services"
+complete -f -c acari -n "__fish_seen_subcommand_from services" -l section -d "group by section"
 complete -f -c acari -n "__fish_seen_subcommand_from services; and __fish_is_arg_n 2" -a "(acari (__profile_arg) -oflat customers)" 
 complete -f -c acari -n "__fish_seen_subcommand_from services; and __fish_is_arg_n 3" -a "(acari (__profile_arg) -oflat projects (__fish_arg_n 2))" 
 
@@ -71,8 +150,24 @@ complete -f -c acari -n "__fish_seen_subcommand_from start; and __fish_is_arg_n
 complete -f -c acari -n "__fish_seen_subcommand_from start; and __fish_is_arg_n 3" -a "(acari (__profile_arg) -oflat projects (__fish_arg_n 2))" 
 complete -f -c acari -n "__fish_seen_subcommand_from start; and __fish_is_arg_n 4" -a "(acari (__profile_arg) -oflat services (__fish_arg_n 2) (__fish_arg_n 3))" 
 
+# summary
+complete -f -c acari -n "__fish_seen_subcommand_from summary"
+complete -f -c acari -n "__fish_seen_subcommand_from summary; and __fish_is_arg_n 2" -a "today yesterday this-week last-week this-month last-month"
+complete -f -c acari -n "__fish_seen_subcommand_from summary" -l by -d "nested grouping" -xa "customer project service note week"
+
 # stop
 complete -f -c acari -n "__fish_seen_subcommand_from stop"
 
 # tracking
 complete -f -c acari -n "__fish_seen_subcommand_from tracking"
+
+# tui
+complete -f -c acari -n "__fish_seen_subcommand_from tui"
+
+# warm-cache
+complete -f -c acari -n "__fish_seen_subcommand_from warm-cache"
+
+# week
+complete -f -c acari -n "__fish_seen_subcommand_from week"
+complete -f -c acari -n "__fish_seen_subcommand_from week; and __fish_is_arg_n 2" -a "today yesterday this-week last-week this-month last-month"
+complete -f -c acari -n "__fish_seen_subcommand_from week" -l csv -d "print as CSV"
@@ -1,32 +1,52 @@
 use super::OutputFormat;
-use super::{entries, find_customer, find_project, find_service};
-use acari_lib::{AcariError, Client, Day, Minutes};
+use super::{check_note_support, entries, resolve_target, split_target};
+use crate::config::Config;
+use acari_lib::{user_error, AcariError, Client, Day, Minutes};
 use clap::Args;
 
 #[derive(Debug, Args, PartialEq, Eq)]
 pub struct AddCmd {
-  #[clap(help = "Customer name")]
-  customer: String,
-  #[clap(help = "Project name")]
-  project: String,
-  #[clap(help = "Service name")]
-  service: String,
-  #[clap(help = "Time (minutes or hh:mm)")]
-  time: Minutes,
-  #[clap(help = "Date", default_value = "today")]
-  day: Day,
+  #[clap(
+    value_name = "ARGS",
+    required = true,
+    num_args = 1..=5,
+    help = "[@<alias> | <customer> <project> <service>] <time (minutes or hh:mm)> [<date>] (customer, project and service may be omitted if set in a .acari.toml)"
+  )]
+  args: Vec<String>,
   #[clap(short, long, help = "Optional note")]
   note: Option<String>,
+  #[clap(long, help = "Include archived customers, projects and services")]
+  archived: bool,
 }
 
 impl AddCmd {
-  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
-    let customer = find_customer(client, &self.customer)?;
-    let project = find_project(client, &customer.id, &self.project)?;
-    let service = find_service(client, &project.id, &self.service)?;
+  pub fn run(&self, client: &dyn Client, config: &Config, output_format: OutputFormat) -> Result<(), AcariError> {
+    let (target, remaining) = split_target(config, &self.args, 1, 2)?;
+    let note = self.note.clone().or_else(|| target.note.clone());
+    check_note_support(client, &note)?;
+    let time: Minutes = remaining[0].parse()?;
+    let day: Day = remaining.get(1).map(|d| d.parse()).transpose()?.unwrap_or(Day::Today);
+    let (_, project, service) = resolve_target(client, &target, self.archived)?;
+    let date = day.as_date();
 
-    client.create_time_entry(self.day, &project.id, &service.id, self.time, self.note.clone())?;
+    if client.capabilities().multiple_entries_per_day {
+      client.create_time_entry(day, &project.id, &service.id, time, note)?;
+    } else {
+      // The backend keeps a single entry per service and day, so add to that explicitly
+      let existing = client
+        .get_time_entries(date.into())?
+        .into_iter()
+        .find(|e| e.date_at == date && e.project_id == project.id && e.service_id == service.id);
 
-    entries(client, output_format, self.day.into())
+      match existing {
+        Some(entry) if entry.locked => return Err(user_error!("Time entry of {} on {} is locked", service.name, date)),
+        Some(entry) => client.update_time_entry(&entry.id, entry.minutes + time, note.or(Some(entry.note).filter(|n| !n.is_empty())))?,
+        None => {
+          client.create_time_entry(day, &project.id, &service.id, time, note)?;
+        }
+      }
+    }
+
+    entries(client, output_format, day.into())
   }
 }
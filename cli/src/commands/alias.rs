@@ -0,0 +1,141 @@
+use super::{check_note_support, resolve_target, EntryTarget, OutputFormat};
+use crate::config::{Alias, Config};
+use acari_lib::{user_error, AcariError, Client};
+use clap::{Args, Subcommand};
+use prettytable::{format, row, Table};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct AliasCmd {
+  #[clap(subcommand)]
+  subcommand: AliasSubCommand,
+}
+
+#[derive(Debug, Subcommand, PartialEq, Eq)]
+enum AliasSubCommand {
+  #[clap(about = "Add (or replace) an alias")]
+  Add {
+    #[clap(help = "Alias name (used as @name)")]
+    name: String,
+    #[clap(help = "Customer name")]
+    customer: String,
+    #[clap(help = "Project name")]
+    project: String,
+    #[clap(help = "Service name")]
+    service: String,
+    #[clap(short, long, help = "Optional default note")]
+    note: Option<String>,
+    #[clap(long, help = "Include archived customers, projects and services")]
+    archived: bool,
+  },
+  #[clap(about = "List all aliases")]
+  List,
+  #[clap(about = "Remove an alias")]
+  Remove {
+    #[clap(help = "Alias name")]
+    name: String,
+  },
+}
+
+impl AliasCmd {
+  /// Only adding an alias has to look up customer, project and service, everything else just touches the configuration.
+  pub fn needs_client(&self) -> bool {
+    matches!(self.subcommand, AliasSubCommand::Add { .. })
+  }
+
+  pub fn run(&self, client: &dyn Client, config: &Config, output_format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    match &self.subcommand {
+      AliasSubCommand::Add {
+        name,
+        customer,
+        project,
+        service,
+        note,
+        archived,
+      } => {
+        let name = name.trim_start_matches('@');
+        if name.is_empty() || name.contains(char::is_whitespace) {
+          return Err(user_error!("Invalid alias name: {}", name).into());
+        }
+        check_note_support(client, note)?;
+
+        let (customer, project, service) = resolve_target(
+          client,
+          &EntryTarget {
+            customer: Some(customer.to_string()),
+            project: Some(project.to_string()),
+            service: Some(service.to_string()),
+            note: None,
+          },
+          *archived,
+        )?;
+        let mut config = config.clone();
+
+        config.aliases.insert(
+          name.to_string(),
+          Alias {
+            customer: customer.name,
+            project: project.name,
+            service: service.name,
+            note: note.clone(),
+          },
+        );
+        config.write()?;
+      }
+      AliasSubCommand::List | AliasSubCommand::Remove { .. } => self.run_without_client(config, output_format)?,
+    }
+
+    Ok(())
+  }
+
+  pub fn run_without_client(&self, config: &Config, output_format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    match &self.subcommand {
+      AliasSubCommand::Add { .. } => unreachable!(),
+      AliasSubCommand::List => match output_format {
+        OutputFormat::Pretty => print_pretty(&config.aliases),
+        OutputFormat::Json => print_json(&config.aliases)?,
+        OutputFormat::Flat => print_flat(&config.aliases),
+      },
+      AliasSubCommand::Remove { name } => {
+        let mut config = config.clone();
+
+        config
+          .aliases
+          .remove(name.trim_start_matches('@'))
+          .ok_or_else(|| user_error!("No such alias: {}", name))?;
+        config.write()?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+fn print_pretty(aliases: &BTreeMap<String, Alias>) {
+  let mut aliases_table = Table::new();
+  aliases_table.set_titles(row!["Alias", "Customer", "Project", "Service", "Note"]);
+  aliases_table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+  for (name, alias) in aliases {
+    aliases_table.add_row(row![
+      format!("@{}", name),
+      alias.customer,
+      alias.project,
+      alias.service,
+      alias.note.as_deref().unwrap_or_default()
+    ]);
+  }
+  aliases_table.printstd();
+}
+
+fn print_json(aliases: &BTreeMap<String, Alias>) -> Result<(), AcariError> {
+  println!("{}", serde_json::to_string_pretty(aliases)?);
+
+  Ok(())
+}
+
+fn print_flat(aliases: &BTreeMap<String, Alias>) {
+  for (name, alias) in aliases {
+    println!("@{}\t{}\t{}\t{}", name, alias.customer, alias.project, alias.service);
+  }
+}
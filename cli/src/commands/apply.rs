@@ -0,0 +1,352 @@
+use super::timesheet::{self, TimesheetLine};
+use super::{check_note_support, entries, resolve_target, short_id, EntryTarget, OutputFormat};
+use crate::config::Config;
+use acari_lib::{user_error, AcariError, Client, DateSpan, Minutes, Project, Service, TimeEntry};
+use chrono::NaiveDate;
+use clap::Args;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct ApplyCmd {
+  #[clap(help = "Timesheet file (lines of <date> <time> <customer>/<project>/<service> [\"<note>\"])")]
+  file: PathBuf,
+  #[clap(long, help = "Only show what would be changed")]
+  dry_run: bool,
+  #[clap(
+    long,
+    help = "Reconcile every day of this span, not only the days mentioned in the file (days without lines are cleared)"
+  )]
+  span: Option<DateSpan>,
+  #[clap(long, help = "Include archived customers, projects and services")]
+  archived: bool,
+}
+
+impl ApplyCmd {
+  pub fn run(&self, client: &dyn Client, config: &Config, output_format: OutputFormat) -> Result<(), AcariError> {
+    let content = fs::read_to_string(&self.file).map_err(|err| user_error!("Unable to read {}: {}", self.file.to_string_lossy(), err))?;
+    let lines = timesheet::parse(&content, &config.aliases)?;
+    let days = match self.span {
+      Some(span) => Days::Span(span.date_range()),
+      None => Days::Mentioned(lines.iter().map(|l| l.date).collect()),
+    };
+    let (from, to) = match days.range() {
+      Some(range) => range,
+      None => {
+        println!("Nothing to apply");
+        return Ok(());
+      }
+    };
+    let existing: Vec<TimeEntry> = client
+      .get_time_entries(DateSpan::FromTo(from, to))?
+      .into_iter()
+      .filter(|e| days.contains(&e.date_at))
+      .collect();
+    let actions = plan(client, lines, existing, self.archived)?;
+
+    if actions.is_empty() {
+      println!("Nothing to change");
+      return Ok(());
+    }
+    for action in &actions {
+      println!("{}", action);
+    }
+    if self.dry_run {
+      return Ok(());
+    }
+    for action in actions {
+      action.apply(client)?;
+    }
+
+    entries(client, output_format, DateSpan::FromTo(from, to))
+  }
+}
+
+/// Days that are reconciled with the timesheet.
+enum Days {
+  Mentioned(BTreeSet<NaiveDate>),
+  Span((NaiveDate, NaiveDate)),
+}
+
+impl Days {
+  fn range(&self) -> Option<(NaiveDate, NaiveDate)> {
+    match self {
+      Days::Mentioned(days) => Some((*days.iter().next()?, *days.iter().next_back()?)),
+      Days::Span(range) => Some(*range),
+    }
+  }
+
+  fn contains(&self, day: &NaiveDate) -> bool {
+    match self {
+      Days::Mentioned(days) => days.contains(day),
+      Days::Span((from, to)) => from <= day && day <= to,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Action {
+  Create { line: TimesheetLine, project: Project, service: Service },
+  Update { entry: TimeEntry, minutes: Minutes, note: String },
+  Delete(TimeEntry),
+}
+
+impl Action {
+  pub(crate) fn apply(self, client: &dyn Client) -> Result<(), AcariError> {
+    match self {
+      Action::Create { line, project, service } => {
+        let note = Some(line.note).filter(|note| !note.is_empty());
+        check_note_support(client, &note)?;
+        client.create_time_entry(line.date.into(), &project.id, &service.id, line.minutes, note)?;
+      }
+      Action::Update { entry, minutes, note } => {
+        let note = Some(note).filter(|note| !note.is_empty());
+        check_note_support(client, &note)?;
+        client.update_time_entry(&entry.id, minutes, note)?;
+      }
+      Action::Delete(entry) => client.delete_time_entry(&entry.id)?,
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for Action {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Action::Create { line, project, service } => write!(
+        f,
+        "Create {} {} {} / {} / {} {}",
+        line.date, line.minutes, project.customer_name, project.name, service.name, line.note
+      ),
+      Action::Update { entry, minutes, note } => write!(
+        f,
+        "Update {} {} {} {} / {} / {} {}",
+        short_id(&entry.id),
+        entry.date_at,
+        minutes,
+        entry.customer_name,
+        entry.project_name,
+        entry.service_name,
+        note
+      ),
+      Action::Delete(entry) => write!(
+        f,
+        "Delete {} {} {} {} / {} / {}",
+        short_id(&entry.id),
+        entry.date_at,
+        entry.minutes,
+        entry.customer_name,
+        entry.project_name,
+        entry.service_name
+      ),
+    }
+  }
+}
+
+type Key = (NaiveDate, String, String);
+
+/// Actions to converge the existing entries to the timesheet lines.
+///
+/// Lines and entries are matched per day, project and service: identical ones are kept, the
+/// remaining ones are updated pairwise and whatever is left over is created or deleted.
+/// If the backend keeps a single entry per day and service, the lines of such an entry are added up.
+pub(crate) fn plan(client: &dyn Client, lines: Vec<TimesheetLine>, existing: Vec<TimeEntry>, include_archived: bool) -> Result<Vec<Action>, AcariError> {
+  let mut resolved: HashMap<(String, String, String), (Project, Service)> = HashMap::new();
+  let mut wanted: BTreeMap<Key, Vec<(TimesheetLine, Project, Service)>> = BTreeMap::new();
+  let mut present: BTreeMap<Key, Vec<TimeEntry>> = BTreeMap::new();
+
+  for line in lines {
+    let names = (line.customer.clone(), line.project.clone(), line.service.clone());
+    let (project, service) = match resolved.get(&names) {
+      Some(target) => target.clone(),
+      None => {
+        let target = EntryTarget {
+          customer: Some(names.0.clone()),
+          project: Some(names.1.clone()),
+          service: Some(names.2.clone()),
+          note: None,
+        };
+        let (_, project, service) = resolve_target(client, &target, include_archived)?;
+        resolved.insert(names, (project.clone(), service.clone()));
+        (project, service)
+      }
+    };
+    let key = (line.date, project.id.str_encoded(), service.id.str_encoded());
+    wanted.entry(key).or_default().push((line, project, service));
+  }
+  if !client.capabilities().multiple_entries_per_day {
+    for lines in wanted.values_mut() {
+      let mut merged = lines.remove(0);
+      for (line, _, _) in lines.drain(..) {
+        merged.0.minutes += line.minutes;
+        merged.0.note = match (merged.0.note.is_empty(), line.note.is_empty()) {
+          (_, true) => merged.0.note,
+          (true, false) => line.note,
+          (false, false) => format!("{}; {}", merged.0.note, line.note),
+        };
+      }
+      lines.push(merged);
+    }
+  }
+  for entry in existing {
+    let key = (entry.date_at, entry.project_id.str_encoded(), entry.service_id.str_encoded());
+    present.entry(key).or_default().push(entry);
+  }
+
+  let keys: BTreeSet<Key> = wanted.keys().chain(present.keys()).cloned().collect();
+  let mut actions = vec![];
+
+  for key in keys {
+    let mut lines = wanted.remove(&key).unwrap_or_default();
+    let mut entries = present.remove(&key).unwrap_or_default();
+    entries.sort_by_key(|e| e.created_at);
+
+    lines.retain(
+      |(line, _, _)| match entries.iter().position(|e| e.minutes == line.minutes && e.note == line.note) {
+        Some(index) => {
+          entries.remove(index);
+          false
+        }
+        None => true,
+      },
+    );
+    for (line, project, service) in lines {
+      match entries.iter().position(|e| !e.locked) {
+        Some(index) => actions.push(Action::Update {
+          entry: entries.remove(index),
+          minutes: line.minutes,
+          note: line.note,
+        }),
+        None => actions.push(Action::Create { line, project, service }),
+      }
+    }
+    for entry in entries {
+      if entry.locked {
+        return Err(user_error!(
+          "Time entry {} on {} ({} / {} / {}) is locked and cannot be changed",
+          short_id(&entry.id),
+          entry.date_at,
+          entry.customer_name,
+          entry.project_name,
+          entry.service_name
+        ));
+      }
+      actions.push(Action::Delete(entry));
+    }
+  }
+
+  Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::commands::test_support::{self, date, mock_client, project, service};
+  use acari_lib::Capabilities;
+
+  fn entry(id: u64, day: u32, minutes: u32, note: &str, locked: bool) -> TimeEntry {
+    TimeEntry {
+      note: note.to_string(),
+      locked,
+      ..test_support::entry(id, day, minutes)
+    }
+  }
+
+  fn line(day: u32, minutes: u32, note: &str) -> TimesheetLine {
+    TimesheetLine {
+      date: date(day),
+      minutes: Minutes(minutes),
+      customer: "acme".to_string(),
+      project: "web".to_string(),
+      service: "dev".to_string(),
+      note: note.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_plan() -> Result<(), AcariError> {
+    let client = mock_client();
+    let lines = vec![line(2, 90, "Login"), line(2, 30, "Review"), line(3, 60, ""), line(6, 15, "")];
+    let existing = vec![
+      entry(1, 2, 30, "Review", false),
+      entry(2, 2, 45, "", false),
+      entry(3, 3, 60, "", true),
+      entry(4, 3, 20, "", false),
+    ];
+
+    assert_eq!(
+      plan(&client, lines, existing, false)?,
+      vec![
+        Action::Update {
+          entry: entry(2, 2, 45, "", false),
+          minutes: Minutes(90),
+          note: "Login".to_string(),
+        },
+        Action::Delete(entry(4, 3, 20, "", false)),
+        Action::Create {
+          line: line(6, 15, ""),
+          project: project(),
+          service: service(),
+        },
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_plan_is_idempotent() -> Result<(), AcariError> {
+    let client = mock_client();
+    let lines = vec![line(2, 90, "Login"), line(2, 90, "Login")];
+
+    for action in plan(&client, lines.clone(), vec![], false)? {
+      action.apply(&client)?;
+    }
+    assert_eq!(client.time_entries().len(), 2);
+    assert_eq!(plan(&client, lines, client.time_entries(), false)?, vec![]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_plan_single_entry_per_day() -> Result<(), AcariError> {
+    let client = mock_client().with_capabilities(Capabilities {
+      multiple_entries_per_day: false,
+      ..mock_client().capabilities()
+    });
+    let lines = vec![line(2, 90, "Login"), line(2, 30, ""), line(2, 15, "Review"), line(3, 60, "")];
+
+    assert_eq!(
+      plan(&client, lines.clone(), vec![entry(1, 2, 60, "", false)], false)?,
+      vec![
+        Action::Update {
+          entry: entry(1, 2, 60, "", false),
+          minutes: Minutes(135),
+          note: "Login; Review".to_string(),
+        },
+        Action::Create {
+          line: line(3, 60, ""),
+          project: project(),
+          service: service(),
+        },
+      ]
+    );
+
+    for action in plan(&client, lines.clone(), vec![], false)? {
+      action.apply(&client)?;
+    }
+    assert_eq!(client.time_entries().len(), 2);
+    assert_eq!(plan(&client, lines, client.time_entries(), false)?, vec![]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_plan_refuses_locked_entries() {
+    let client = mock_client();
+
+    assert!(plan(&client, vec![], vec![entry(1, 2, 30, "", true)], false).is_err());
+  }
+}
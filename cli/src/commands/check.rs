@@ -1,22 +1,23 @@
 use super::OutputFormat;
-use acari_lib::{AcariError, Account, Client, User};
+use acari_lib::{AcariError, Account, Capabilities, Client, User};
 use prettytable::{format, table};
 use serde_json::json;
 
 pub fn check(client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
   let account = client.get_account()?;
   let user = client.get_myself()?;
+  let capabilities = client.capabilities();
 
   match output_format {
-    OutputFormat::Pretty => print_pretty(account, user),
-    OutputFormat::Json => print_json(account, user)?,
+    OutputFormat::Pretty => print_pretty(account, user, capabilities),
+    OutputFormat::Json => print_json(account, user, capabilities)?,
     OutputFormat::Flat => print_flat(account, user),
   }
 
   Ok(())
 }
 
-fn print_pretty(account: Account, user: User) {
+fn print_pretty(account: Account, user: User, capabilities: Capabilities) {
   let mut account_table = table!(
     ["Id", account.id],
     ["Name", account.name],
@@ -42,14 +43,37 @@ fn print_pretty(account: Account, user: User) {
   println!("User");
   user_table.set_format(*format::consts::FORMAT_CLEAN);
   user_table.printstd();
+
+  let mut capabilities_table = table!(
+    ["Tracker", yes_no(capabilities.tracker)],
+    ["Notes", yes_no(capabilities.notes)],
+    ["Billable flag", yes_no(capabilities.billable)],
+    ["Multiple entries per day", yes_no(capabilities.multiple_entries_per_day)],
+    ["Locking", yes_no(capabilities.locking)],
+    ["Team queries", yes_no(capabilities.team_queries)]
+  );
+
+  println!();
+  println!("Capabilities");
+  capabilities_table.set_format(*format::consts::FORMAT_CLEAN);
+  capabilities_table.printstd();
+}
+
+fn yes_no(flag: bool) -> &'static str {
+  if flag {
+    "yes"
+  } else {
+    "no"
+  }
 }
 
-fn print_json(account: Account, user: User) -> Result<(), AcariError> {
+fn print_json(account: Account, user: User, capabilities: Capabilities) -> Result<(), AcariError> {
   println!(
     "{}",
     serde_json::to_string_pretty(&json!({
       "account": account,
       "user": user,
+      "capabilities": capabilities,
     }))?
   );
 
@@ -0,0 +1,119 @@
+use super::OutputFormat;
+use crate::config::{config_file, Config};
+use acari_lib::{user_error, AcariError};
+use clap::{Args, Subcommand};
+use prettytable::{format, row, Table};
+use serde_json::json;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct ConfigCmd {
+  #[clap(subcommand)]
+  subcommand: ConfigSubCommand,
+}
+
+#[derive(Debug, Subcommand, PartialEq, Eq)]
+enum ConfigSubCommand {
+  #[clap(about = "Show the configuration (without tokens)")]
+  Show {
+    #[clap(long, help = "Show the effective settings after merging config.toml, .acari.toml and command line")]
+    resolved: bool,
+  },
+}
+
+impl ConfigCmd {
+  pub fn run(&self, maybe_config: Option<Config>, maybe_profile: &Option<String>, output_format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let config = maybe_config.ok_or_else(|| user_error!("Missing configuration, run init first"))?;
+
+    match &self.subcommand {
+      ConfigSubCommand::Show { resolved: false } => show_files(&config, output_format)?,
+      ConfigSubCommand::Show { resolved: true } => show_resolved(&config, maybe_profile, output_format)?,
+    }
+
+    Ok(())
+  }
+}
+
+fn show_files(config: &Config, output_format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+  let redacted = config.redacted();
+
+  match output_format {
+    OutputFormat::Json => println!(
+      "{}",
+      serde_json::to_string_pretty(&json!({
+        "config": redacted,
+        "local": config.local.path.as_ref().map(|_| &config.local),
+      }))?
+    ),
+    OutputFormat::Pretty | OutputFormat::Flat => {
+      println!("# {}", config_file().to_string_lossy());
+      println!("{}", toml::to_string_pretty(&redacted)?);
+      if let Some(path) = &config.local.path {
+        println!("# {}", path.to_string_lossy());
+        println!("{}", toml::to_string_pretty(&config.local)?);
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn show_resolved(config: &Config, maybe_profile: &Option<String>, output_format: OutputFormat) -> Result<(), AcariError> {
+  let profile_name = config.profile_name(maybe_profile);
+  let profile = config.profile(&profile_name)?;
+  let profile_source = match (maybe_profile, &config.local.profile, &config.default_profile) {
+    (Some(_), _, _) => "command line".to_string(),
+    (None, Some(_), _) => config.local.path.as_ref().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
+    (None, None, Some(_)) => "default_profile".to_string(),
+    (None, None, None) => "top level".to_string(),
+  };
+  let local_path = config.local.path.as_ref().map(|p| p.to_string_lossy().to_string());
+  let mut settings: Vec<(&str, Option<String>)> = vec![
+    ("Profile", Some(profile_name.clone().unwrap_or_else(|| "(top level)".to_string()))),
+    ("Profile from", Some(profile_source)),
+    ("Backend", Some(format!("{:?}", profile.client))),
+    ("Domain", Some(profile.domain.clone())),
+    ("Token", Some(profile.credentials.describe())),
+    (
+      "Cache TTL (minutes)",
+      Some(profile.cache_ttl_minutes.unwrap_or(config.cache_ttl_minutes).to_string()),
+    ),
+    ("Base url", profile.connection.base_url.clone()),
+    ("Proxy", profile.connection.proxy.clone()),
+    ("User agent", profile.connection.user_agent.clone()),
+    ("Local config", local_path),
+    ("Customer", config.local.customer.clone()),
+    ("Project", config.local.project.clone()),
+    ("Service", config.local.service.clone()),
+  ];
+  settings.retain(|(_, value)| value.is_some());
+
+  match output_format {
+    OutputFormat::Pretty => {
+      let mut settings_table = Table::new();
+      settings_table.set_format(*format::consts::FORMAT_CLEAN);
+      for (name, value) in settings {
+        settings_table.add_row(row![name, value.unwrap_or_default()]);
+      }
+      settings_table.printstd();
+    }
+    OutputFormat::Json => println!(
+      "{}",
+      serde_json::to_string_pretty(&json!({
+        "profile": profile_name,
+        "client": profile.client,
+        "domain": profile.domain,
+        "token": profile.credentials.describe(),
+        "cache_ttl_minutes": profile.cache_ttl_minutes.unwrap_or(config.cache_ttl_minutes),
+        "connection": profile.connection,
+        "local": config.local.path.as_ref().map(|_| &config.local),
+      }))?
+    ),
+    OutputFormat::Flat => {
+      for (name, value) in settings {
+        println!("{}\t{}", name, value.unwrap_or_default());
+      }
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,258 @@
+use super::{check_note_support, entries, OutputFormat};
+use acari_lib::{user_error, AcariError, Client, DateSpan, Minutes, TimeEntry};
+use chrono::NaiveDate;
+use clap::Args;
+use std::collections::HashSet;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct CopyCmd {
+  #[clap(help = "Date span to copy from\n(today, yesterday, this-week, last-week,\n this-month, last-month, yyyy-mm-dd, yyyy-mm-dd/yyyy-mm-dd)")]
+  from: DateSpan,
+  #[clap(help = "Day or date span to copy to")]
+  to: DateSpan,
+  #[clap(
+    long,
+    help = "Shift entries by the distance between the start of both spans (keeps weekdays for weeks), required if the target spans multiple days"
+  )]
+  shift: bool,
+  #[clap(long, help = "Do not copy notes")]
+  no_notes: bool,
+  #[clap(long, help = "Do not copy the time, i.e. create entries with 0:00")]
+  no_time: bool,
+  #[clap(long, help = "Skip target days that already have entries")]
+  skip_existing: bool,
+  #[clap(long, help = "Only show what would be copied")]
+  dry_run: bool,
+}
+
+impl CopyCmd {
+  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
+    let mut source_entries = client.get_time_entries(self.from)?;
+    source_entries.sort_by(|t1, t2| t1.date_at.cmp(&t2.date_at).then_with(|| t1.created_at.cmp(&t2.created_at)));
+
+    let copies = map_dates(&source_entries, self.from.date_range(), self.to.date_range(), self.shift)?;
+    let single_entry_per_day = !client.capabilities().multiple_entries_per_day;
+    let existing = if self.skip_existing || single_entry_per_day {
+      client.get_time_entries(self.to)?
+    } else {
+      vec![]
+    };
+    let occupied: HashSet<NaiveDate> = existing.iter().map(|e| e.date_at).collect();
+
+    let mut planned: Vec<(NaiveDate, &TimeEntry, Minutes, Option<String>)> = vec![];
+    for (date, entry) in copies {
+      if self.skip_existing && occupied.contains(&date) {
+        continue;
+      }
+      let minutes = if self.no_time { Minutes::default() } else { entry.minutes };
+      let note = Some(entry.note.clone()).filter(|note| !self.no_notes && !note.is_empty());
+      check_note_support(client, &note)?;
+      if let Some(locked) = existing
+        .iter()
+        .find(|e| single_entry_per_day && e.locked && e.date_at == date && e.project_id == entry.project_id && e.service_id == entry.service_id)
+      {
+        return Err(user_error!("Time entry of {} on {} is locked", locked.service_name, date));
+      }
+
+      // The backend keeps a single entry per service and day, so copies onto the same one are added up
+      match planned
+        .iter_mut()
+        .find(|(d, e, _, _)| single_entry_per_day && *d == date && e.project_id == entry.project_id && e.service_id == entry.service_id)
+      {
+        Some((_, _, total, joined_note)) => {
+          *total += minutes;
+          *joined_note = join_notes(joined_note.take(), note);
+        }
+        None => planned.push((date, entry, minutes, note)),
+      }
+    }
+    if planned.is_empty() {
+      println!("Nothing to copy");
+      return Ok(());
+    }
+
+    for (date, entry, minutes, note) in planned {
+      let target = existing
+        .iter()
+        .find(|e| single_entry_per_day && e.date_at == date && e.project_id == entry.project_id && e.service_id == entry.service_id);
+
+      match target {
+        _ if self.dry_run => println!(
+          "Copy {} {} {} / {} / {} to {}",
+          entry.date_at, minutes, entry.customer_name, entry.project_name, entry.service_name, date
+        ),
+        Some(target) => {
+          let existing_note = Some(target.note.clone()).filter(|note| !note.is_empty());
+          client.update_time_entry(&target.id, target.minutes + minutes, join_notes(existing_note, note))?;
+        }
+        None => {
+          client.create_time_entry(date.into(), &entry.project_id, &entry.service_id, minutes, note)?;
+        }
+      }
+    }
+    if self.dry_run {
+      return Ok(());
+    }
+
+    entries(client, output_format, self.to)
+  }
+}
+
+fn join_notes(first: Option<String>, second: Option<String>) -> Option<String> {
+  match (first, second) {
+    (Some(first), Some(second)) => Some(format!("{}; {}", first, second)),
+    (first, second) => first.or(second),
+  }
+}
+
+/// Target date of each entry.
+///
+/// Without shifting everything is copied to a single target day, otherwise entries are moved by the
+/// distance between the start of the spans and those ending up outside of the target are dropped.
+fn map_dates(
+  entries: &[TimeEntry],
+  (from_start, _): (NaiveDate, NaiveDate),
+  (to_start, to_end): (NaiveDate, NaiveDate),
+  shift: bool,
+) -> Result<Vec<(NaiveDate, &TimeEntry)>, AcariError> {
+  if !shift {
+    if to_start != to_end {
+      return Err(user_error!("Target spans multiple days, use --shift to keep the distribution over the days"));
+    }
+    return Ok(entries.iter().map(|e| (to_start, e)).collect());
+  }
+  let offset = to_start - from_start;
+
+  Ok(
+    entries
+      .iter()
+      .map(|e| (e.date_at + offset, e))
+      .filter(|(date, _)| to_start <= *date && *date <= to_end)
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::commands::test_support::{date, entry, mock_client};
+  use acari_lib::{Capabilities, Mutation, ProjectId, ServiceId, TimeEntryId};
+
+  fn copy_cmd(from: (u32, u32), to: (u32, u32), shift: bool) -> CopyCmd {
+    CopyCmd {
+      from: DateSpan::FromTo(date(from.0), date(from.1)),
+      to: DateSpan::FromTo(date(to.0), date(to.1)),
+      shift,
+      no_notes: false,
+      no_time: false,
+      skip_existing: false,
+      dry_run: false,
+    }
+  }
+
+  fn created(day: u32, minutes: u32, note: Option<&str>) -> Mutation {
+    Mutation::CreateTimeEntry {
+      date: date(day),
+      project_id: ProjectId::Num(10),
+      service_id: ServiceId::Num(100),
+      minutes: Minutes(minutes),
+      note: note.map(ToString::to_string),
+    }
+  }
+
+  fn target_dates(mapped: Vec<(NaiveDate, &TimeEntry)>) -> Vec<(u64, NaiveDate)> {
+    mapped
+      .into_iter()
+      .map(|(date, e)| match e.id {
+        TimeEntryId::Num(id) => (id, date),
+        _ => unreachable!(),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_map_dates() -> Result<(), AcariError> {
+    // Monday 2024-05-06 to Sunday 2024-05-12
+    let last_week = vec![entry(1, 6, 60), entry(2, 8, 60), entry(3, 12, 60)];
+
+    assert_eq!(
+      target_dates(map_dates(&last_week, (date(6), date(12)), (date(13), date(19)), true)?),
+      vec![(1, date(13)), (2, date(15)), (3, date(19))]
+    );
+    assert_eq!(
+      target_dates(map_dates(&last_week, (date(6), date(12)), (date(20), date(20)), false)?),
+      vec![(1, date(20)), (2, date(20)), (3, date(20))]
+    );
+    assert_eq!(
+      target_dates(map_dates(&last_week, (date(6), date(12)), (date(7), date(11)), true)?),
+      vec![(1, date(7)), (2, date(9))]
+    );
+    assert!(map_dates(&last_week, (date(6), date(12)), (date(13), date(19)), false).is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_run() -> Result<(), AcariError> {
+    let login = TimeEntry {
+      note: "Login".to_string(),
+      ..entry(2, 8, 30)
+    };
+    let client = mock_client().with_time_entry(entry(1, 6, 60)).with_time_entry(login);
+
+    copy_cmd((6, 12), (13, 19), true).run(&client, OutputFormat::Flat)?;
+    assert_eq!(client.mutations(), vec![created(13, 60, None), created(15, 30, Some("Login"))]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_run_single_entry_per_day() -> Result<(), AcariError> {
+    let client = mock_client()
+      .with_capabilities(Capabilities {
+        multiple_entries_per_day: false,
+        ..mock_client().capabilities()
+      })
+      .with_time_entry(entry(1, 6, 60))
+      .with_time_entry(TimeEntry {
+        note: "Login".to_string(),
+        ..entry(2, 8, 30)
+      })
+      .with_time_entry(TimeEntry {
+        note: "Review".to_string(),
+        ..entry(3, 20, 15)
+      });
+
+    copy_cmd((6, 12), (20, 20), false).run(&client, OutputFormat::Flat)?;
+    assert_eq!(
+      client.mutations(),
+      vec![Mutation::UpdateTimeEntry {
+        entry_id: TimeEntryId::Num(3),
+        minutes: Minutes(105),
+        note: Some("Review; Login".to_string()),
+      }]
+    );
+
+    copy_cmd((6, 12), (21, 21), false).run(&client, OutputFormat::Flat)?;
+    assert_eq!(client.mutations()[1], created(21, 90, Some("Login")));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_run_without_note_support() {
+    let client = mock_client()
+      .with_capabilities(Capabilities {
+        notes: false,
+        ..mock_client().capabilities()
+      })
+      .with_time_entry(entry(1, 6, 60))
+      .with_time_entry(TimeEntry {
+        note: "Login".to_string(),
+        ..entry(2, 8, 30)
+      });
+
+    assert!(copy_cmd((6, 12), (13, 19), true).run(&client, OutputFormat::Flat).is_err());
+    assert_eq!(client.mutations(), vec![]);
+  }
+}
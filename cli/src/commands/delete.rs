@@ -0,0 +1,37 @@
+use super::picker::{confirm, is_interactive};
+use super::{entries, find_entry, OutputFormat};
+use acari_lib::{user_error, AcariError, Client};
+use clap::Args;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct DeleteCmd {
+  #[clap(help = "Id of the time entry (as shown by entries)")]
+  id: String,
+  #[clap(short, long, help = "Do not ask for confirmation")]
+  yes: bool,
+}
+
+impl DeleteCmd {
+  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
+    let entry = find_entry(client, &self.id)?;
+
+    if entry.locked {
+      return Err(user_error!("Time entry {} is locked", self.id));
+    }
+    if !self.yes {
+      if !is_interactive() {
+        return Err(user_error!("Refusing to delete without confirmation, use --yes"));
+      }
+      let question = format!(
+        "Delete {} {} {} / {} / {}?",
+        entry.date_at, entry.minutes, entry.customer_name, entry.project_name, entry.service_name
+      );
+      if !confirm(&question)? {
+        return Ok(());
+      }
+    }
+    client.delete_time_entry(&entry.id)?;
+
+    entries(client, output_format, entry.date_at.into())
+  }
+}
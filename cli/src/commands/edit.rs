@@ -0,0 +1,33 @@
+use super::{check_note_support, entries, find_entry, OutputFormat};
+use acari_lib::{user_error, AcariError, Client, Minutes};
+use clap::Args;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct EditCmd {
+  #[clap(help = "Id of the time entry (as shown by entries)")]
+  id: String,
+  #[clap(short, long, help = "New time (minutes or hh:mm)")]
+  time: Option<Minutes>,
+  #[clap(short, long, help = "New note (empty to clear)")]
+  note: Option<String>,
+}
+
+impl EditCmd {
+  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
+    if self.time.is_none() && self.note.is_none() {
+      return Err(user_error!("Nothing to change, use --time and/or --note"));
+    }
+    check_note_support(client, &self.note)?;
+    let entry = find_entry(client, &self.id)?;
+
+    if entry.locked {
+      return Err(user_error!("Time entry {} is locked", self.id));
+    }
+
+    let minutes = self.time.unwrap_or(entry.minutes);
+    let note = self.note.clone().or(Some(entry.note)).filter(|note| !note.is_empty());
+    client.update_time_entry(&entry.id, minutes, note)?;
+
+    entries(client, output_format, entry.date_at.into())
+  }
+}
@@ -0,0 +1,497 @@
+use super::picker::{confirm, is_interactive};
+use super::{check_note_support, entries, resolve_target, short_id, EntryTarget, OutputFormat};
+use acari_lib::{internal_error, user_error, AcariError, Client, DateSpan, Minutes, Project, Service, TimeEntry};
+use chrono::NaiveDate;
+use clap::Args;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+const COLUMNS: [&str; 7] = ["Id", "Date", "Time", "Customer", "Project", "Service", "Note"];
+const NEW_ID: &str = "new";
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct EditSpanCmd {
+  #[clap(help = "Date span to edit\n(today, yesterday, this-week, last-week,\n this-month, last-month, yyyy-mm-dd, yyyy-mm-dd/yyyy-mm-dd)")]
+  span: DateSpan,
+  #[clap(short, long, help = "Apply the changes without asking for confirmation")]
+  yes: bool,
+}
+
+impl EditSpanCmd {
+  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
+    let mut time_entries = client.get_time_entries(self.span)?;
+    time_entries.sort_by(|t1, t2| t1.date_at.cmp(&t2.date_at).then_with(|| t1.created_at.cmp(&t2.created_at)));
+
+    // Only readable by the user and removed when dropped, unless the changes have to be kept
+    let mut file = tempfile::Builder::new().prefix("acari-edit-").suffix(".txt").tempfile()?;
+    file.write_all(render(&self.span, &time_entries).as_bytes())?;
+    run_editor(file.path())?;
+    let edited = fs::read_to_string(file.path())?;
+    let planned = match plan(client, &time_entries, &edited) {
+      Ok(planned) => planned,
+      Err(err) => {
+        let (_, path) = file.keep().map_err(|err| err.error)?;
+        return Err(user_error!("{} (your changes are kept in {})", message(err), path.to_string_lossy()));
+      }
+    };
+    drop(file);
+
+    if planned.is_empty() {
+      println!("No changes");
+      return Ok(());
+    }
+    for (change, target) in &planned {
+      match (change, target) {
+        (Change::Create(line), Some((project, service))) => println!(
+          "Create {} {} {} / {} / {} {}",
+          line.date, line.minutes, project.customer_name, project.name, service.name, line.note
+        ),
+        (change, _) => println!("{}", change),
+      }
+    }
+    if !self.yes {
+      if !is_interactive() {
+        return Err(user_error!("Refusing to apply changes without confirmation, use --yes"));
+      }
+      if !confirm(&format!("Apply {} change(s)?", planned.len()))? {
+        return Ok(());
+      }
+    }
+    for (change, target) in planned {
+      apply(client, change, target)?;
+    }
+
+    entries(client, output_format, self.span)
+  }
+}
+
+/// A modification of the time entries resulting from the edited buffer.
+#[derive(Debug, PartialEq, Eq)]
+enum Change {
+  Create(EditedLine),
+  Update { entry: TimeEntry, minutes: Minutes, note: String },
+  Delete(TimeEntry),
+}
+
+impl std::fmt::Display for Change {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Change::Create(line) => write!(
+        f,
+        "Create {} {} {} / {} / {} {}",
+        line.date, line.minutes, line.customer, line.project, line.service, line.note
+      ),
+      Change::Update { entry, minutes, note } => write!(
+        f,
+        "Update {} {} {} / {} / {} {}",
+        entry.date_at, minutes, entry.customer_name, entry.project_name, entry.service_name, note
+      ),
+      Change::Delete(entry) => write!(
+        f,
+        "Delete {} {} {} / {} / {}",
+        entry.date_at, entry.minutes, entry.customer_name, entry.project_name, entry.service_name
+      ),
+    }
+  }
+}
+
+/// A change together with project and service of a new entry.
+type PlannedChange = (Change, Option<(Project, Service)>);
+
+/// A line of the edited buffer, without id if it should be created.
+#[derive(Debug, PartialEq, Eq)]
+struct EditedLine {
+  id: Option<String>,
+  date: NaiveDate,
+  minutes: Minutes,
+  customer: String,
+  project: String,
+  service: String,
+  note: String,
+}
+
+/// Render the entries as a table, locked entries are commented out so that they are read-only.
+fn render(span: &DateSpan, time_entries: &[TimeEntry]) -> String {
+  let (from, to) = span.date_range();
+  let rows: Vec<(bool, [String; 7])> = time_entries
+    .iter()
+    .map(|e| {
+      (
+        e.locked,
+        [
+          short_id(&e.id),
+          e.date_at.to_string(),
+          e.minutes.to_string(),
+          e.customer_name.clone(),
+          e.project_name.clone(),
+          e.service_name.clone(),
+          escape_note(&e.note),
+        ],
+      )
+    })
+    .collect();
+  let mut widths = COLUMNS.map(str::len);
+  for (_, row) in &rows {
+    for (width, value) in widths.iter_mut().zip(row) {
+      *width = (*width).max(value.chars().count());
+    }
+  }
+  let format_row = |row: &[&str]| {
+    let columns: Vec<String> = row
+      .iter()
+      .zip(&widths)
+      .map(|(value, width)| format!("{:<width$}", value, width = width))
+      .collect();
+    columns.join(" | ").trim_end().to_string()
+  };
+
+  let mut buffer = format!(
+    "# Time entries from {} to {}\n\
+     #\n\
+     # Change time or note of a line to update the entry, remove a line to delete it.\n\
+     # Add a line with id '{}' to create an entry. Changing date, customer, project or\n\
+     # service replaces the entry. Locked entries are commented out and cannot be changed.\n\
+     # In notes \\n is a line break, \\t a tab, \\s a leading or trailing space and \\\\ a backslash.\n\
+     #\n\
+     # {}\n",
+    from,
+    to,
+    NEW_ID,
+    format_row(&COLUMNS)
+  );
+  for (locked, row) in &rows {
+    let line = format_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    if *locked {
+      buffer.push_str(&format!("# {} (locked)\n", line));
+    } else {
+      buffer.push_str(&format!("  {}\n", line));
+    }
+  }
+  buffer
+}
+
+fn parse(buffer: &str) -> Result<Vec<EditedLine>, AcariError> {
+  buffer
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+    .map(|(index, line)| parse_line(line).map_err(|err| user_error!("Line {}: {}", index + 1, message(err))))
+    .collect()
+}
+
+fn parse_line(line: &str) -> Result<EditedLine, AcariError> {
+  let columns: Vec<&str> = line.splitn(COLUMNS.len(), '|').map(str::trim).collect();
+  if columns.len() < COLUMNS.len() - 1 {
+    return Err(user_error!("Expected columns {}", COLUMNS.join(" | ")));
+  }
+  let name = |index: usize| match columns[index] {
+    "" => Err(user_error!("Missing {}", COLUMNS[index].to_lowercase())),
+    name => Ok(name.to_string()),
+  };
+
+  Ok(EditedLine {
+    id: Some(columns[0]).filter(|id| !id.is_empty() && *id != NEW_ID).map(str::to_string),
+    date: NaiveDate::parse_from_str(columns[1], "%Y-%m-%d")?,
+    minutes: columns[2].parse()?,
+    customer: name(3)?,
+    project: name(4)?,
+    service: name(5)?,
+    note: columns.get(6).map(|note| unescape_note(note)).unwrap_or_default(),
+  })
+}
+
+/// Keep a note on a single line of the buffer: line breaks, tabs and backslashes are escaped, as
+/// well as leading and trailing spaces that would otherwise be trimmed when parsing.
+fn escape_note(note: &str) -> String {
+  let chars: Vec<char> = note.chars().collect();
+  let first = chars.iter().position(|c| *c != ' ').unwrap_or(chars.len());
+  let last = chars.iter().rposition(|c| *c != ' ').map_or(first, |index| index + 1);
+  let mut escaped = String::with_capacity(note.len());
+  for (index, c) in chars.into_iter().enumerate() {
+    match c {
+      '\\' => escaped.push_str("\\\\"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      '\t' => escaped.push_str("\\t"),
+      ' ' if index < first || index >= last => escaped.push_str("\\s"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// Reverse of `escape_note`, unknown escapes are kept as they are.
+fn unescape_note(note: &str) -> String {
+  let mut unescaped = String::with_capacity(note.len());
+  let mut chars = note.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      unescaped.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('\\') => unescaped.push('\\'),
+      Some('n') => unescaped.push('\n'),
+      Some('r') => unescaped.push('\r'),
+      Some('t') => unescaped.push('\t'),
+      Some('s') => unescaped.push(' '),
+      Some(other) => {
+        unescaped.push('\\');
+        unescaped.push(other);
+      }
+      None => unescaped.push('\\'),
+    }
+  }
+  unescaped
+}
+
+/// Compare the edited lines with the original entries.
+fn diff(original: &[TimeEntry], lines: Vec<EditedLine>) -> Result<Vec<Change>, AcariError> {
+  let mut seen: Vec<&TimeEntry> = vec![];
+  let mut updates = vec![];
+  let mut creates = vec![];
+
+  for line in lines {
+    let id = match &line.id {
+      Some(id) => id,
+      None => {
+        creates.push(Change::Create(line));
+        continue;
+      }
+    };
+    let entry = original
+      .iter()
+      .find(|e| short_id(&e.id) == *id)
+      .ok_or_else(|| user_error!("Unknown id {} (use '{}' for new entries)", id, NEW_ID))?;
+    if entry.locked {
+      return Err(user_error!("Time entry {} is locked", id));
+    }
+    if seen.iter().any(|e| e.id == entry.id) {
+      return Err(user_error!("Time entry {} appears more than once", id));
+    }
+    seen.push(entry);
+
+    if line.date != entry.date_at || line.customer != entry.customer_name || line.project != entry.project_name || line.service != entry.service_name {
+      updates.push(Change::Delete(entry.clone()));
+      creates.push(Change::Create(line));
+    } else if line.minutes != entry.minutes || line.note != entry.note {
+      updates.push(Change::Update {
+        entry: entry.clone(),
+        minutes: line.minutes,
+        note: line.note,
+      });
+    }
+  }
+
+  let mut changes: Vec<Change> = original
+    .iter()
+    .filter(|e| !e.locked && !seen.iter().any(|s| s.id == e.id))
+    .map(|e| Change::Delete(e.clone()))
+    .collect();
+  changes.extend(updates);
+  changes.extend(creates);
+
+  Ok(changes)
+}
+
+/// Changes of the edited buffer, notes are checked and project and service of new entries are
+/// resolved up front so that nothing is applied if one of them is wrong.
+fn plan(client: &dyn Client, original: &[TimeEntry], buffer: &str) -> Result<Vec<PlannedChange>, AcariError> {
+  diff(original, parse(buffer)?)?
+    .into_iter()
+    .map(|change| match &change {
+      Change::Create(line) => {
+        let target = resolve_line(client, line)?;
+        Ok((change, Some(target)))
+      }
+      Change::Update { note, .. } => {
+        check_note_support(client, &Some(note.clone()).filter(|note| !note.is_empty()))?;
+        Ok((change, None))
+      }
+      Change::Delete(_) => Ok((change, None)),
+    })
+    .collect()
+}
+
+fn resolve_line(client: &dyn Client, line: &EditedLine) -> Result<(Project, Service), AcariError> {
+  check_note_support(client, &Some(line.note.clone()).filter(|note| !note.is_empty()))?;
+  let target = EntryTarget {
+    customer: Some(line.customer.clone()),
+    project: Some(line.project.clone()),
+    service: Some(line.service.clone()),
+    note: None,
+  };
+  let (_, project, service) = resolve_target(client, &target, false)?;
+
+  Ok((project, service))
+}
+
+fn apply(client: &dyn Client, change: Change, target: Option<(Project, Service)>) -> Result<(), AcariError> {
+  match (change, target) {
+    (Change::Create(line), Some((project, service))) => {
+      let note = Some(line.note).filter(|note| !note.is_empty());
+      client.create_time_entry(line.date.into(), &project.id, &service.id, line.minutes, note)?;
+    }
+    (Change::Create(_), None) => return Err(internal_error!("Unresolved time entry")),
+    (Change::Update { entry, minutes, note }, _) => {
+      let note = Some(note).filter(|note| !note.is_empty());
+      client.update_time_entry(&entry.id, minutes, note)?;
+    }
+    (Change::Delete(entry), _) => client.delete_time_entry(&entry.id)?,
+  }
+  Ok(())
+}
+
+/// Avoid repeating the "User error" prefix when wrapping errors.
+fn message(err: AcariError) -> String {
+  match err {
+    AcariError::UserError(message) => message,
+    err => err.to_string(),
+  }
+}
+
+fn run_editor(path: &Path) -> Result<(), AcariError> {
+  let editor = env::var("VISUAL").or_else(|_| env::var("EDITOR")).unwrap_or_else(|_| "vi".to_string());
+  // The editor may come with arguments (e.g. "code --wait")
+  #[cfg(windows)]
+  let status = Command::new("cmd")
+    .arg("/C")
+    .arg(format!("{} \"{}\"", editor, path.to_string_lossy()))
+    .status()?;
+  #[cfg(not(windows))]
+  let status = Command::new("sh").arg("-c").arg(format!("{} \"$1\"", editor)).arg("sh").arg(path).status()?;
+
+  if !status.success() {
+    return Err(user_error!("Editor {} failed ({})", editor, status));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::commands::test_support::{self, date};
+  use acari_lib::Capabilities;
+
+  fn entry(id: u64, day: u32, minutes: u32, locked: bool) -> TimeEntry {
+    TimeEntry {
+      locked,
+      ..test_support::entry(id, day, minutes)
+    }
+  }
+
+  #[test]
+  fn test_render_and_parse() -> Result<(), AcariError> {
+    let original = vec![entry(1, 6, 90, false), entry(2, 7, 30, true)];
+    let buffer = render(&DateSpan::FromTo(date(6), date(12)), &original);
+
+    assert!(buffer.contains("  1  | 2024-05-06 | 1:30 | ACME"));
+    assert!(buffer.contains("# 2  | 2024-05-07 | 0:30 | ACME"));
+    assert_eq!(
+      parse(&buffer)?,
+      vec![EditedLine {
+        id: Some("1".to_string()),
+        date: date(6),
+        minutes: Minutes(90),
+        customer: "ACME".to_string(),
+        project: "Website".to_string(),
+        service: "Development".to_string(),
+        note: "".to_string(),
+      }]
+    );
+    assert_eq!(diff(&original, parse(&buffer)?)?, vec![]);
+
+    assert!(parse("1 | 2024-05-06 | 1:30 | ACME").is_err());
+    assert!(parse("1 | 2024-05-06 | soon | ACME | Website | Development").is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_render_and_parse_notes() -> Result<(), AcariError> {
+    let notes = ["First line\nsecond\tline", "  indented ", " ", "C:\\temp\\new | more"];
+    let original: Vec<TimeEntry> = notes
+      .iter()
+      .enumerate()
+      .map(|(index, note)| TimeEntry {
+        note: note.to_string(),
+        ..entry(index as u64 + 1, 6, 30, false)
+      })
+      .collect();
+    let buffer = render(&DateSpan::FromTo(date(6), date(12)), &original);
+
+    assert!(buffer.contains("| First line\\nsecond\\tline\n"));
+    assert!(buffer.contains("| \\s\\sindented\\s\n"));
+    assert_eq!(parse(&buffer)?.into_iter().map(|line| line.note).collect::<Vec<_>>(), notes);
+    assert_eq!(diff(&original, parse(&buffer)?)?, vec![]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_plan_checks_notes() {
+    let client = test_support::mock_client()
+      .with_capabilities(Capabilities {
+        notes: false,
+        ..test_support::mock_client().capabilities()
+      })
+      .with_time_entry(entry(1, 6, 90, false));
+    let buffer = "
+      new | 2024-05-07 | 0:45 | ACME | Website | Development
+      1   | 2024-05-06 | 1:30 | ACME | Website | Development | Login
+    ";
+
+    assert!(plan(&client, &[entry(1, 6, 90, false)], buffer).is_err());
+    assert_eq!(client.mutations(), vec![]);
+  }
+
+  #[test]
+  fn test_diff() -> Result<(), AcariError> {
+    let original = vec![entry(1, 6, 90, false), entry(2, 7, 30, true), entry(3, 8, 60, false), entry(4, 9, 15, false)];
+    let buffer = "
+      1   | 2024-05-06 | 2:00 | ACME | Website | Development | More work | really
+      new | 2024-05-10 | 0:45 | acme | web     | dev
+      4   | 2024-05-10 | 0:15 | ACME | Website | Development
+    ";
+    let changes = diff(&original, parse(buffer)?)?;
+
+    assert_eq!(
+      changes,
+      vec![
+        Change::Delete(entry(3, 8, 60, false)),
+        Change::Update {
+          entry: entry(1, 6, 90, false),
+          minutes: Minutes(120),
+          note: "More work | really".to_string(),
+        },
+        Change::Delete(entry(4, 9, 15, false)),
+        Change::Create(EditedLine {
+          id: None,
+          date: date(10),
+          minutes: Minutes(45),
+          customer: "acme".to_string(),
+          project: "web".to_string(),
+          service: "dev".to_string(),
+          note: "".to_string(),
+        }),
+        Change::Create(EditedLine {
+          id: Some("4".to_string()),
+          date: date(10),
+          minutes: Minutes(15),
+          customer: "ACME".to_string(),
+          project: "Website".to_string(),
+          service: "Development".to_string(),
+          note: "".to_string(),
+        }),
+      ]
+    );
+
+    assert!(diff(&original, parse("2 | 2024-05-07 | 1:00 | ACME | Website | Development")?).is_err());
+    assert!(diff(&original, parse("5 | 2024-05-07 | 1:00 | ACME | Website | Development")?).is_err());
+
+    Ok(())
+  }
+}
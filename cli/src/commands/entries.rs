@@ -1,6 +1,6 @@
 use super::OutputFormat;
-use acari_lib::{AcariError, Client, DateSpan, Minutes, TimeEntry};
-use chrono::NaiveDate;
+use acari_lib::{user_error, AcariError, Client, DateSpan, Day, Minutes, TimeEntry, TimeEntryId};
+use chrono::{Duration, NaiveDate};
 use clap::Args;
 use itertools::Itertools;
 use prettytable::{format, row, Table};
@@ -19,17 +19,11 @@ impl EntriesCmd {
 }
 
 pub fn entries(client: &dyn Client, output_format: OutputFormat, date_span: DateSpan) -> Result<(), AcariError> {
-  let tracker = client.get_tracker()?;
-  let mut time_entries = client.get_time_entries(date_span)?;
+  let (mut time_entries, tracker) = client.get_time_entries_with_tracker(date_span)?;
 
   time_entries.sort_by(|t1, t2| t1.date_at.cmp(&t2.date_at));
 
-  let grouped: Vec<(&NaiveDate, Vec<&TimeEntry>)> = time_entries
-    .iter()
-    .chunk_by(|e| &e.date_at)
-    .into_iter()
-    .map(|(customer_name, group)| (customer_name, group.collect()))
-    .collect();
+  let grouped = group_by_day(&time_entries);
 
   match output_format {
     OutputFormat::Pretty => print_pretty(grouped, &tracker.tracking_time_entry),
@@ -40,6 +34,65 @@ pub fn entries(client: &dyn Client, output_format: OutputFormat, date_span: Date
   Ok(())
 }
 
+/// Group time entries by day, the entries have to be sorted by day already.
+pub(crate) fn group_by_day(time_entries: &[TimeEntry]) -> Vec<(&NaiveDate, Vec<&TimeEntry>)> {
+  time_entries
+    .iter()
+    .chunk_by(|e| &e.date_at)
+    .into_iter()
+    .map(|(day, group)| (day, group.collect()))
+    .collect()
+}
+
+/// Minutes of an entry including the time tracked so far if it is currently tracking.
+pub(crate) fn current_minutes(entry: &TimeEntry, tracking_time_entry: &Option<TimeEntry>) -> Minutes {
+  match tracking_time_entry.as_ref().filter(|t| t.id == entry.id) {
+    Some(tracking_entry) => tracking_entry.minutes,
+    None => entry.minutes,
+  }
+}
+
+/// Short id of a time entry to refer to it on the command line.
+///
+/// Numeric ids are short enough by themselves, all others (e.g. the composite ids of everhour) are
+/// condensed to a stable hash.
+pub(crate) fn short_id(entry_id: &TimeEntryId) -> String {
+  match entry_id {
+    TimeEntryId::Num(n) => n.to_string(),
+    TimeEntryId::Str(s) => {
+      // FNV-1a, which unlike the std hashers is guaranteed to be stable
+      let hash = s.bytes().fold(0x811c_9dc5u32, |hash, b| (hash ^ u32::from(b)).wrapping_mul(0x0100_0193));
+      format!("{:08x}", hash)
+    }
+  }
+}
+
+/// Find a time entry by its short id.
+///
+/// Numeric ids are looked up directly, everything else (as well as numeric ids that are not found)
+/// is searched among the entries of the last three months.
+pub(crate) fn find_entry(client: &dyn Client, id: &str) -> Result<TimeEntry, AcariError> {
+  if let Ok(n) = id.parse::<u64>() {
+    match client.get_time_entry(&TimeEntryId::Num(n)) {
+      Ok(entry) => return Ok(entry),
+      Err(AcariError::NotFound(_)) => (),
+      Err(err) => return Err(err),
+    }
+  }
+  let today = Day::Today.as_date();
+  let mut matching: Vec<TimeEntry> = client
+    .get_time_entries(DateSpan::FromTo(today - Duration::days(92), today + Duration::days(31)))?
+    .into_iter()
+    .filter(|e| short_id(&e.id) == id || e.id.to_string() == id)
+    .collect();
+
+  match matching.len() {
+    0 => Err(user_error!("No time entry with id {}", id)),
+    1 => Ok(matching.remove(0)),
+    _ => Err(user_error!("Time entry id {} is ambiguous", id)),
+  }
+}
+
 fn print_pretty(entries: Vec<(&NaiveDate, Vec<&TimeEntry>)>, tracking_time_entry: &Option<TimeEntry>) {
   if entries.is_empty() {
     println!("No entries found");
@@ -49,35 +102,35 @@ fn print_pretty(entries: Vec<(&NaiveDate, Vec<&TimeEntry>)>, tracking_time_entry
   let mut total: Minutes = Default::default();
   let show_total = entries.len() > 1;
   let mut entries_table = Table::new();
-  entries_table.set_titles(row!["Day", "Time", "Customer", "Project", "Service", "Note"]);
+  entries_table.set_titles(row!["Day", "Id", "Time", "Customer", "Project", "Service", "Note"]);
   entries_table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
 
   for (day, group) in entries {
-    let sum = group
-      .iter()
-      .map(|e| {
-        if let Some(tracking_entry) = tracking_time_entry.as_ref().filter(|t| t.id == e.id) {
-          tracking_entry.minutes
-        } else {
-          e.minutes
-        }
-      })
-      .sum::<Minutes>();
+    let sum = group.iter().map(|e| current_minutes(e, tracking_time_entry)).sum::<Minutes>();
     total += sum;
-    entries_table.add_row(row![bFc -> day, bFc -> sum, "", "", "", ""]);
+    entries_table.add_row(row![bFc -> day, "", bFc -> sum, "", "", "", ""]);
     for entry in group {
       if let Some(tracking_entry) = tracking_time_entry.as_ref().filter(|t| t.id == entry.id) {
-        entries_table.add_row(row![FY => "", tracking_entry.minutes, entry.customer_name, entry.project_name, entry.service_name, entry.note]);
+        entries_table
+          .add_row(row![FY => "", short_id(&entry.id), tracking_entry.minutes, entry.customer_name, entry.project_name, entry.service_name, entry.note]);
       } else if entry.locked {
-        entries_table.add_row(row![Fr => "", entry.minutes, entry.customer_name, entry.project_name, entry.service_name, entry.note]);
+        entries_table.add_row(row![Fr => "", short_id(&entry.id), entry.minutes, entry.customer_name, entry.project_name, entry.service_name, entry.note]);
       } else {
-        entries_table.add_row(row!["", entry.minutes, entry.customer_name, entry.project_name, entry.service_name, entry.note]);
+        entries_table.add_row(row![
+          "",
+          short_id(&entry.id),
+          entry.minutes,
+          entry.customer_name,
+          entry.project_name,
+          entry.service_name,
+          entry.note
+        ]);
       }
     }
   }
   if show_total {
-    entries_table.add_row(row!["", "-----", "", "", "", ""]);
-    entries_table.add_row(row!["", bFw -> total, "", "", "", ""]);
+    entries_table.add_row(row!["", "", "-----", "", "", "", ""]);
+    entries_table.add_row(row!["", "", bFw -> total, "", "", "", ""]);
   }
 
   entries_table.printstd();
@@ -88,6 +141,7 @@ fn print_json(entries: Vec<TimeEntry>, tracking_time_entry: &Option<TimeEntry>)
     .into_iter()
     .map(|entry| match serde_json::to_value(&entry)? {
       Value::Object(mut fields) => {
+        fields.insert("short_id".to_string(), Value::String(short_id(&entry.id)));
         if let Some(tracking_entry) = tracking_time_entry.as_ref().filter(|t| t.id == entry.id) {
           fields.insert("tracking".to_string(), Value::Bool(true));
           fields["minutes"] = json!(tracking_entry.minutes);
@@ -109,20 +163,84 @@ fn print_flat(entries: Vec<(&NaiveDate, Vec<&TimeEntry>)>, tracking_time_entry:
     for entry in group {
       if let Some(tracking_entry) = tracking_time_entry.as_ref().filter(|t| t.id == entry.id) {
         println!(
-          "{}\t{}\t{}\t{}\t{}\tTRACKING",
-          date, entry.customer_name, entry.project_name, entry.service_name, tracking_entry.minutes,
+          "{}\t{}\t{}\t{}\t{}\tTRACKING\t{}",
+          date,
+          entry.customer_name,
+          entry.project_name,
+          entry.service_name,
+          tracking_entry.minutes,
+          short_id(&entry.id),
         );
       } else if entry.locked {
         println!(
-          "{}\t{}\t{}\t{}\t{}\tLOCKED",
-          date, entry.customer_name, entry.project_name, entry.service_name, entry.minutes,
+          "{}\t{}\t{}\t{}\t{}\tLOCKED\t{}",
+          date,
+          entry.customer_name,
+          entry.project_name,
+          entry.service_name,
+          entry.minutes,
+          short_id(&entry.id),
         );
       } else {
         println!(
-          "{}\t{}\t{}\t{}\t{}\tOPEN",
-          date, entry.customer_name, entry.project_name, entry.service_name, entry.minutes,
+          "{}\t{}\t{}\t{}\t{}\tOPEN\t{}",
+          date,
+          entry.customer_name,
+          entry.project_name,
+          entry.service_name,
+          entry.minutes,
+          short_id(&entry.id),
         );
       }
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::commands::test_support;
+  use acari_lib::{MockClient, MockOperation};
+
+  fn entry(id: TimeEntryId) -> TimeEntry {
+    TimeEntry {
+      id,
+      date_at: Day::Today.as_date(),
+      ..test_support::entry(1, 6, 30)
+    }
+  }
+
+  #[test]
+  fn test_short_id() {
+    assert_eq!(short_id(&TimeEntryId::Num(1234)), "1234");
+    assert_eq!(short_id(&TimeEntryId::Str("n1|n100|2024-05-02".to_string())), "2810bbb6");
+    assert_eq!(short_id(&TimeEntryId::Str("".to_string())), "811c9dc5");
+  }
+
+  #[test]
+  fn test_find_entry() -> Result<(), AcariError> {
+    let everhour_id = TimeEntryId::Str("n1|n100|2024-05-02".to_string());
+    let client = MockClient::new()
+      .with_time_entry(entry(TimeEntryId::Num(42)))
+      .with_time_entry(entry(everhour_id.clone()));
+
+    assert_eq!(find_entry(&client, "42")?.id, TimeEntryId::Num(42));
+    assert_eq!(find_entry(&client, &short_id(&everhour_id))?.id, everhour_id);
+    assert_eq!(find_entry(&client, "n1|n100|2024-05-02")?.id, everhour_id);
+    assert!(find_entry(&client, "43").is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_find_entry_fails_on_errors() -> Result<(), AcariError> {
+    let client = MockClient::new()
+      .with_time_entry(entry(TimeEntryId::Num(42)))
+      .with_error(MockOperation::GetTimeEntry, AcariError::Mite(503, "Service unavailable".to_string()));
+
+    assert!(matches!(find_entry(&client, "42"), Err(AcariError::Mite(503, _))));
+    assert_eq!(find_entry(&client, "42")?.id, TimeEntryId::Num(42));
+
+    Ok(())
+  }
+}
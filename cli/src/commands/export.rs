@@ -0,0 +1,30 @@
+use super::timesheet::TimesheetLine;
+use super::{entries, OutputFormat};
+use acari_lib::{AcariError, Client, DateSpan};
+use clap::Args;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct ExportCmd {
+  #[clap(help = "Date span to export\n(today, yesterday, this-week, last-week,\n this-month, last-month, yyyy-mm-dd, yyyy-mm-dd/yyyy-mm-dd)")]
+  span: DateSpan,
+  #[clap(long, help = "Export as timesheet that can be used with apply")]
+  timesheet: bool,
+}
+
+impl ExportCmd {
+  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
+    if !self.timesheet {
+      return entries(client, output_format, self.span);
+    }
+    let mut time_entries = client.get_time_entries(self.span)?;
+    time_entries.sort_by(|t1, t2| t1.date_at.cmp(&t2.date_at).then_with(|| t1.created_at.cmp(&t2.created_at)));
+
+    let (from, to) = self.span.date_range();
+    println!("# Time entries from {} to {}", from, to);
+    for entry in &time_entries {
+      println!("{}", TimesheetLine::from_entry(entry).render());
+    }
+
+    Ok(())
+  }
+}
@@ -1,50 +1,206 @@
-use crate::config::{ClientType, Config, Profile};
+use crate::config::{ClientType, Config, Profile, TokenSource};
+use acari_lib::{user_error, AcariError, Secret};
+use clap::{ArgGroup, Args};
+use std::fs::{self, OpenOptions};
 use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
 use text_io::try_read;
 
-pub fn init(maybe_existing_config: Option<Config>, maybe_profile: &Option<String>) -> Result<(), Box<dyn std::error::Error>> {
-  let mut config = match maybe_existing_config {
-    Some(existing) => {
-      let confirm = match maybe_profile {
-        Some(profile) if existing.profiles.contains_key(profile) => Some(format!("Overwrite existing {} profile (yes/No): ", profile)),
-        None if !existing.domain.is_empty() || !existing.token.is_empty() => Some("Overwrite existing default profile (yes/No): ".to_string()),
-        _ => None,
+#[derive(Debug, Args, PartialEq, Eq)]
+#[clap(group(ArgGroup::new("token_source").args(["token", "token_env", "token_command", "token_file"])))]
+pub struct InitCmd {
+  #[clap(long, help = "Backend to use")]
+  backend: Option<ClientType>,
+  #[clap(long, help = "Domain of the account (e.g. <your-company>.mite.yo.lk)")]
+  domain: Option<String>,
+  #[clap(long, help = "API token (will be stored in plain text)")]
+  token: Option<String>,
+  #[clap(long, help = "Read the API token from this environment variable")]
+  token_env: Option<String>,
+  #[clap(long, help = "Read the API token from the output of this command")]
+  token_command: Option<String>,
+  #[clap(long, help = "Read the API token from this file")]
+  token_file: Option<PathBuf>,
+  #[clap(
+    short,
+    long,
+    help = "Do not ask any questions, use defaults for everything not given and overwrite existing profiles"
+  )]
+  yes: bool,
+  #[clap(long, help = "Do not check the credentials before saving")]
+  skip_check: bool,
+}
+
+impl InitCmd {
+  pub fn run(&self, maybe_existing_config: Option<Config>, maybe_profile: &Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = maybe_existing_config.unwrap_or_else(|| Config {
+      cache_ttl_minutes: 1440,
+      ..Default::default()
+    });
+    let profile_name = config.profile_name(maybe_profile);
+    let existing = config.profile(&profile_name).ok();
+
+    if existing.is_some() && !self.yes {
+      let msg = match &profile_name {
+        Some(name) => format!("Overwrite existing {} profile (yes/No): ", name),
+        None => "Overwrite existing default profile (yes/No): ".to_string(),
       };
-      if let Some(msg) = confirm {
-        if console_input(&msg)? != "yes" {
-          return Ok(());
-        }
+      if console_input(&msg)? != "yes" {
+        return Ok(());
       }
-      existing
     }
-    None => Config {
-      cache_ttl_minutes: 1440,
-      ..Default::default()
-    },
-  };
 
-  let domain: String = console_input("Mite domain: ")?;
-  let token: String = console_input("API Token: ")?;
+    let client = self.backend()?;
+    let domain = self.domain(client)?;
+    let credentials = self.token_source()?;
+    let profile = Profile {
+      domain,
+      credentials,
+      client,
+      ..existing.unwrap_or_default()
+    };
 
-  match maybe_profile {
-    Some(profile) => {
-      config.profiles.insert(
-        profile.to_string(),
-        Profile {
-          domain,
-          token,
-          client: ClientType::Mite,
-        },
-      );
+    if !self.skip_check {
+      check_profile(&profile)?;
     }
-    None => {
-      config.domain = domain;
-      config.token = token;
+
+    match profile_name {
+      Some(name) => {
+        config.profiles.insert(name, profile);
+      }
+      None => config.set_default_profile(profile),
+    };
+    config.write()?;
+
+    println!("Configuration updated");
+
+    Ok(())
+  }
+
+  fn backend(&self) -> Result<ClientType, Box<dyn std::error::Error>> {
+    if let Some(backend) = self.backend {
+      return Ok(backend);
     }
-  };
-  config.write()?;
+    if self.yes {
+      return Ok(ClientType::default());
+    }
+    match console_input("Backend (mite/everhour) [mite]: ")?.as_str() {
+      "" | "mite" => Ok(ClientType::Mite),
+      "everhour" => Ok(ClientType::Everhour),
+      other => Err(user_error!("Unknown backend: {}", other).into()),
+    }
+  }
 
-  println!("Configuration updated");
+  fn domain(&self, client: ClientType) -> Result<String, Box<dyn std::error::Error>> {
+    if let Some(domain) = &self.domain {
+      return Ok(domain.to_string());
+    }
+    let domain = match (client.default_domain(), self.yes) {
+      (Some(default_domain), true) => default_domain.to_string(),
+      (None, true) => return Err(user_error!("Missing --domain").into()),
+      (Some(default_domain), false) => match console_input(&format!("Everhour domain [{}]: ", default_domain))? {
+        domain if domain.is_empty() => default_domain.to_string(),
+        domain => domain,
+      },
+      (None, false) => console_input("Mite domain (e.g. <your-company>.mite.yo.lk): ")?,
+    };
+    if domain.is_empty() {
+      return Err(user_error!("Domain must not be empty").into());
+    }
+    Ok(domain)
+  }
+
+  fn token_source(&self) -> Result<TokenSource, Box<dyn std::error::Error>> {
+    if let Some(token) = &self.token {
+      return Ok(TokenSource {
+        token: Secret::new(token.as_str()),
+        ..Default::default()
+      });
+    }
+    if let Some(name) = &self.token_env {
+      return Ok(TokenSource {
+        token_env: Some(name.to_string()),
+        ..Default::default()
+      });
+    }
+    if let Some(command) = &self.token_command {
+      return Ok(TokenSource {
+        token_command: Some(command.to_string()),
+        ..Default::default()
+      });
+    }
+    if let Some(path) = &self.token_file {
+      if !path.exists() {
+        if self.yes {
+          return Err(user_error!("Token file {} does not exist", path.to_string_lossy()).into());
+        }
+        write_token_file(path, &Secret::new(console_input("API Token: ")?))?;
+      }
+      return Ok(TokenSource {
+        token_file: Some(path.clone()),
+        ..Default::default()
+      });
+    }
+    if self.yes {
+      return Err(user_error!("Missing one of --token, --token-env, --token-command or --token-file").into());
+    }
+    token_source()
+  }
+}
+
+fn check_profile(profile: &Profile) -> Result<(), Box<dyn std::error::Error>> {
+  let client = profile.client(None)?;
+  let account = client
+    .get_account()
+    .map_err(|err| user_error!("Unable to verify credentials ({}), use --skip-check to save anyway", err))?;
+  let user = client.get_myself()?;
+
+  println!("Logged in as {} <{}> at {}", user.name, user.email, account.title);
+
+  Ok(())
+}
+
+fn token_source() -> Result<TokenSource, Box<dyn std::error::Error>> {
+  let source = console_input("Store API token (inline/env/command/file) [inline]: ")?;
+
+  Ok(match source.as_str() {
+    "" | "inline" => TokenSource {
+      token: Secret::new(console_input("API Token: ")?),
+      ..Default::default()
+    },
+    "env" => TokenSource {
+      token_env: Some(console_input("Environment variable containing the API token: ")?),
+      ..Default::default()
+    },
+    "command" => TokenSource {
+      token_command: Some(console_input("Command printing the API token (e.g. 'pass show mite'): ")?),
+      ..Default::default()
+    },
+    "file" => {
+      let path = PathBuf::from(console_input("Token file: ")?);
+      if !path.exists() {
+        write_token_file(&path, &Secret::new(console_input("API Token: ")?))?;
+      }
+      TokenSource {
+        token_file: Some(path),
+        ..Default::default()
+      }
+    }
+    other => return Err(user_error!("Unknown token source: {}", other).into()),
+  })
+}
+
+fn write_token_file(path: &Path, token: &Secret) -> Result<(), AcariError> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  let mut options = OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+  let mut file = options.open(path)?;
+  writeln!(file, "{}", token.expose())?;
 
   Ok(())
 }
@@ -57,3 +213,27 @@ fn console_input(msg: &str) -> Result<String, Box<dyn std::error::Error>> {
 
   Ok(input.trim().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_write_token_file() -> Result<(), AcariError> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("acari").join("token");
+
+    write_token_file(&path, &Secret::new("secret"))?;
+    assert_eq!(fs::read_to_string(&path)?, "secret\n");
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      assert_eq!(fs::metadata(&path)?.permissions().mode() & 0o777, 0o600);
+    }
+
+    assert!(write_token_file(&path, &Secret::new("other")).is_err());
+    assert_eq!(fs::read_to_string(&path)?, "secret\n");
+
+    Ok(())
+  }
+}
@@ -0,0 +1,282 @@
+use super::picker::{is_interactive, pick};
+use acari_lib::{user_error, AcariError, Customer, Project, Service};
+use itertools::Itertools;
+
+/// Maximum number of candidates shown as suggestion or in the picker.
+const MAX_CANDIDATES: usize = 10;
+
+pub trait Named {
+  fn name(&self) -> &str;
+
+  fn archived(&self) -> bool;
+}
+
+impl Named for Customer {
+  fn name(&self) -> &str {
+    &self.name
+  }
+
+  fn archived(&self) -> bool {
+    self.archived
+  }
+}
+
+impl Named for Project {
+  fn name(&self) -> &str {
+    &self.name
+  }
+
+  fn archived(&self) -> bool {
+    self.archived
+  }
+}
+
+impl Named for Service {
+  fn name(&self) -> &str {
+    &self.name
+  }
+
+  fn archived(&self) -> bool {
+    self.archived
+  }
+}
+
+/// How well a name matches a query, lower is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchRank {
+  Exact,
+  CaseInsensitive,
+  Prefix,
+  WordPrefix,
+  Contains,
+  /// All characters of the query appear in order, ranked by the number of skipped characters
+  Subsequence(usize),
+  /// Probably a typo, ranked by edit distance
+  Typo(usize),
+}
+
+impl MatchRank {
+  fn is_exact(self) -> bool {
+    matches!(self, MatchRank::Exact | MatchRank::CaseInsensitive)
+  }
+}
+
+fn match_rank(query: &str, name: &str) -> Option<MatchRank> {
+  if name == query {
+    return Some(MatchRank::Exact);
+  }
+  let query = query.to_lowercase();
+  let name = name.to_lowercase();
+
+  if name == query {
+    Some(MatchRank::CaseInsensitive)
+  } else if name.starts_with(&query) {
+    Some(MatchRank::Prefix)
+  } else if name.split(|c: char| !c.is_alphanumeric()).any(|word| word.starts_with(&query)) {
+    Some(MatchRank::WordPrefix)
+  } else if name.contains(&query) {
+    Some(MatchRank::Contains)
+  } else if let Some(skipped) = subsequence_gaps(&query, &name) {
+    Some(MatchRank::Subsequence(skipped))
+  } else {
+    let distance = edit_distance(&query, &name);
+    if distance <= (query.chars().count() / 3).max(1) {
+      Some(MatchRank::Typo(distance))
+    } else {
+      None
+    }
+  }
+}
+
+fn subsequence_gaps(query: &str, name: &str) -> Option<usize> {
+  let mut name_chars = name.chars();
+  let mut skipped = 0;
+
+  for q in query.chars().filter(|c| !c.is_whitespace()) {
+    loop {
+      match name_chars.next() {
+        Some(n) if n == q => break,
+        Some(_) => skipped += 1,
+        None => return None,
+      }
+    }
+  }
+  Some(skipped)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+  for (i, ca) in a.chars().enumerate() {
+    let mut current = vec![i + 1; b.len() + 1];
+    for (j, cb) in b.iter().enumerate() {
+      let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+      current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+    }
+    previous = current;
+  }
+  previous[b.len()]
+}
+
+enum Resolution<T> {
+  NotFound,
+  Found(T),
+  Ambiguous(Vec<T>),
+}
+
+/// A single (exact or case insensitive) match of the full name is resolved directly, as is a query matching only one candidate at all.
+/// Everything else (e.g. a prefix of one and a substring of another name) is ambiguous.
+fn resolve<T: Named>(query: &str, candidates: Vec<T>, include_archived: bool) -> Resolution<T> {
+  let mut ranked: Vec<(MatchRank, T)> = candidates
+    .into_iter()
+    .filter(|c| include_archived || !c.archived())
+    .filter_map(|c| match_rank(query, c.name()).map(|rank| (rank, c)))
+    .collect();
+  ranked.sort_by(|(r1, c1), (r2, c2)| r1.cmp(r2).then_with(|| c1.name().cmp(c2.name())));
+
+  let best = match ranked.first() {
+    Some((rank, _)) => *rank,
+    None => return Resolution::NotFound,
+  };
+  let best_count = ranked.iter().take_while(|(rank, _)| *rank == best).count();
+
+  if ranked.len() == 1 || (best_count == 1 && best.is_exact()) {
+    Resolution::Found(ranked.remove(0).1)
+  } else {
+    Resolution::Ambiguous(ranked.into_iter().take(MAX_CANDIDATES).map(|(_, c)| c).collect())
+  }
+}
+
+/// Find the candidate best matching the query or let the user pick one if there is none, see
+/// `find_best_match` and `pick_candidate`. Unless the user may be asked, an ambiguous or missing
+/// query is an error.
+pub fn find_match<T: Named>(kind: &str, maybe_query: Option<&str>, candidates: Vec<T>, include_archived: bool, ask: bool) -> Result<T, AcariError> {
+  match maybe_query {
+    Some(query) if ask => find_best_match(kind, query, candidates, include_archived),
+    Some(query) => find_unique_match(kind, query, candidates, include_archived),
+    None if ask => pick_candidate(kind, candidates, include_archived),
+    None => Err(user_error!("Missing {}", kind)),
+  }
+}
+
+/// Find the candidate best matching the query, if ambiguous the user may pick one (if on a terminal)
+/// or gets a list of suggestions.
+fn find_best_match<T: Named>(kind: &str, query: &str, candidates: Vec<T>, include_archived: bool) -> Result<T, AcariError> {
+  match resolve(query, candidates, include_archived) {
+    Resolution::Ambiguous(mut candidates) if is_interactive() => {
+      let names: Vec<&str> = candidates.iter().map(|c| c.name()).collect();
+      match pick(&format!("Multiple {}s match '{}':", kind, query), &names)? {
+        Some(index) => Ok(candidates.remove(index)),
+        None => Err(user_error!("No {} selected", kind)),
+      }
+    }
+    resolution => unique_match(kind, query, resolution),
+  }
+}
+
+/// Find the candidate best matching the query without ever asking the user.
+fn find_unique_match<T: Named>(kind: &str, query: &str, candidates: Vec<T>, include_archived: bool) -> Result<T, AcariError> {
+  unique_match(kind, query, resolve(query, candidates, include_archived))
+}
+
+fn unique_match<T: Named>(kind: &str, query: &str, resolution: Resolution<T>) -> Result<T, AcariError> {
+  match resolution {
+    Resolution::NotFound => Err(user_error!("No {} with name: {}", kind, query)),
+    Resolution::Found(candidate) => Ok(candidate),
+    Resolution::Ambiguous(candidates) => Err(user_error!(
+      "Ambiguous {} '{}', did you mean: {}",
+      kind,
+      query,
+      candidates.iter().map(|c| c.name()).join(", ")
+    )),
+  }
+}
+
+/// Let the user pick one of all candidates, fails if not on a terminal.
+fn pick_candidate<T: Named>(kind: &str, mut candidates: Vec<T>, include_archived: bool) -> Result<T, AcariError> {
+  if !is_interactive() {
+    return Err(user_error!("Missing {}", kind));
+  }
+  candidates.retain(|c| include_archived || !c.archived());
+  candidates.sort_by(|c1, c2| c1.name().cmp(c2.name()));
+  if candidates.is_empty() {
+    return Err(user_error!("No {} to choose from", kind));
+  }
+
+  let names: Vec<&str> = candidates.iter().map(|c| c.name()).collect();
+  match pick(&format!("Choose {}:", kind), &names)? {
+    Some(index) => Ok(candidates.remove(index)),
+    None => Err(user_error!("No {} selected", kind)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct Item(&'static str, bool);
+
+  impl Named for Item {
+    fn name(&self) -> &str {
+      self.0
+    }
+
+    fn archived(&self) -> bool {
+      self.1
+    }
+  }
+
+  fn items() -> Vec<Item> {
+    vec![
+      Item("ACME GmbH", false),
+      Item("Acme Labs", false),
+      Item("Globex", false),
+      Item("Initech", true),
+      Item("Website Relaunch", false),
+    ]
+  }
+
+  #[test]
+  fn test_match_rank() {
+    assert_eq!(match_rank("Globex", "Globex"), Some(MatchRank::Exact));
+    assert_eq!(match_rank("globex", "Globex"), Some(MatchRank::CaseInsensitive));
+    assert_eq!(match_rank("glo", "Globex"), Some(MatchRank::Prefix));
+    assert_eq!(match_rank("relaunch", "Website Relaunch"), Some(MatchRank::WordPrefix));
+    assert_eq!(match_rank("bex", "Globex"), Some(MatchRank::Contains));
+    assert_eq!(match_rank("wsr", "Website Relaunch"), Some(MatchRank::Subsequence(6)));
+    assert_eq!(match_rank("glboex", "Globex"), Some(MatchRank::Typo(2)));
+    assert_eq!(match_rank("initech", "Globex"), None);
+  }
+
+  fn resolved_name(query: &str, include_archived: bool) -> Option<&'static str> {
+    match resolve(query, items(), include_archived) {
+      Resolution::Found(item) => Some(item.0),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn test_resolve() {
+    assert_eq!(resolved_name("globex", false), Some("Globex"));
+    assert_eq!(resolved_name("acme gmbh", false), Some("ACME GmbH"));
+    assert_eq!(resolved_name("relaunch", false), Some("Website Relaunch"));
+    assert_eq!(resolved_name("initech", false), None);
+    assert_eq!(resolved_name("initech", true), Some("Initech"));
+    assert_eq!(resolved_name("unknown", true), None);
+
+    match resolve("acme", items(), false) {
+      Resolution::Ambiguous(candidates) => assert_eq!(candidates.iter().map(|c| c.0).collect::<Vec<_>>(), vec!["ACME GmbH", "Acme Labs"]),
+      _ => panic!("Expected ambiguous result"),
+    }
+
+    match resolve("sign", vec![Item("Design", false), Item("Signage", false)], false) {
+      Resolution::Ambiguous(candidates) => assert_eq!(candidates.iter().map(|c| c.0).collect::<Vec<_>>(), vec!["Signage", "Design"]),
+      _ => panic!("Expected ambiguous result"),
+    }
+    match resolve("sign", vec![Item("Design", false), Item("Signage", false), Item("Sign", false)], false) {
+      Resolution::Found(item) => assert_eq!(item.0, "Sign"),
+      _ => panic!("Expected case insensitive match"),
+    }
+  }
+}
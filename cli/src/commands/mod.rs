@@ -1,32 +1,71 @@
 use clap::ValueEnum;
 
 mod add;
+mod alias;
 mod all_projects;
+mod apply;
 mod check;
+mod config;
+mod copy;
 mod customers;
+mod delete;
+mod edit;
+mod edit_span;
 mod entries;
+mod export;
 mod init;
+mod matching;
+mod picker;
+mod profile;
 mod profiles;
 mod projects;
 mod projects_of_customer;
+mod recurring;
 mod services;
 mod set;
+mod summary;
+#[cfg(test)]
+mod test_support;
+mod timesheet;
 mod tracker;
+#[cfg(feature = "tui")]
+mod tui;
+mod warm_cache;
+mod week;
 
 pub use add::*;
+pub use alias::*;
 pub use all_projects::*;
+pub use apply::*;
 pub use check::*;
+pub use config::*;
+pub use copy::*;
 pub use customers::*;
+pub use delete::*;
+pub use edit::*;
+pub use edit_span::*;
 pub use entries::*;
+pub use export::*;
 pub use init::*;
+pub use profile::*;
 pub use profiles::*;
 pub use projects::*;
 pub use projects_of_customer::*;
+pub use recurring::*;
 pub use services::*;
 pub use set::*;
+pub use summary::*;
 pub use tracker::*;
+#[cfg(feature = "tui")]
+pub use tui::*;
+pub use warm_cache::*;
+pub use week::*;
 
-use acari_lib::{user_error, AcariError, Client, Customer, CustomerId, Project, ProjectId, Service};
+use crate::config::{Alias, Config};
+use acari_lib::{user_error, AcariError, Client, Customer, CustomerId, Day, Minutes, Project, ProjectId, Service};
+use matching::find_match;
+use picker::is_interactive;
+use std::collections::BTreeMap;
 
 #[derive(ValueEnum, Debug, PartialEq, Clone, Copy)]
 pub enum OutputFormat {
@@ -35,29 +74,237 @@ pub enum OutputFormat {
   Flat,
 }
 
-fn find_customer(client: &dyn Client, customer_name: &str) -> Result<Customer, AcariError> {
-  let customers = client.get_customers()?;
+fn check_tracker_support(client: &dyn Client) -> Result<(), AcariError> {
+  if client.capabilities().tracker {
+    Ok(())
+  } else {
+    Err(user_error!("Time tracking is not supported by {}", client.get_domain()))
+  }
+}
+
+fn check_note_support(client: &dyn Client, note: &Option<String>) -> Result<(), AcariError> {
+  if note.is_none() || client.capabilities().notes {
+    Ok(())
+  } else {
+    Err(user_error!("Notes are not supported by {}", client.get_domain()))
+  }
+}
+
+/// Names of customer, project and service of a time entry, `None` if they should be picked interactively.
+#[derive(Debug, PartialEq, Eq)]
+struct EntryTarget {
+  customer: Option<String>,
+  project: Option<String>,
+  service: Option<String>,
+  note: Option<String>,
+}
+
+/// Split the positional arguments into the target of a time entry and the remaining arguments.
+/// The target is either given by an `@alias`, as three names or may be omitted if defaults are
+/// configured in a `.acari.toml`. Names may also be given partially (e.g. just the customer), everything
+/// missing is taken from the defaults or picked interactively on a terminal.
+fn split_target<'a>(config: &Config, args: &'a [String], min_remaining: usize, max_remaining: usize) -> Result<(EntryTarget, &'a [String]), AcariError> {
+  let (target, remaining) = if let Some(alias_name) = args.first().and_then(|arg| arg.strip_prefix('@')) {
+    (alias_target(&config.aliases, alias_name)?, &args[1..])
+  } else if args.len() >= 3 + min_remaining {
+    (
+      EntryTarget {
+        customer: Some(args[0].to_string()),
+        project: Some(args[1].to_string()),
+        service: Some(args[2].to_string()),
+        note: None,
+      },
+      &args[3..],
+    )
+  } else {
+    let names = leading_names(args);
+    let target = EntryTarget {
+      customer: args[..names].first().or(config.local.customer.as_ref()).cloned(),
+      project: args[..names].get(1).or(config.local.project.as_ref()).cloned(),
+      service: args[..names].get(2).or(config.local.service.as_ref()).cloned(),
+      note: None,
+    };
+    // Pick whatever is neither given nor set in the defaults
+    if (target.customer.is_none() || target.project.is_none() || target.service.is_none()) && !is_interactive() {
+      return Err(user_error!("Missing customer, project and service (defaults may be set in a .acari.toml)"));
+    }
+    (target, &args[names..])
+  };
+  if remaining.len() < min_remaining || remaining.len() > max_remaining {
+    return Err(user_error!("Unexpected number of arguments: {}", args.join(" ")));
+  }
+  Ok((target, remaining))
+}
+
+fn alias_target(aliases: &BTreeMap<String, Alias>, alias_name: &str) -> Result<EntryTarget, AcariError> {
+  let alias = aliases.get(alias_name).ok_or_else(|| user_error!("No such alias: {}", alias_name))?;
+
+  Ok(EntryTarget {
+    customer: Some(alias.customer.to_string()),
+    project: Some(alias.project.to_string()),
+    service: Some(alias.service.to_string()),
+    note: alias.note.clone(),
+  })
+}
+
+/// Number of leading arguments that are neither a time nor a day, i.e. (the first) names of a target.
+fn leading_names(args: &[String]) -> usize {
+  args
+    .iter()
+    .take(3)
+    .take_while(|arg| arg.parse::<Minutes>().is_err() && arg.parse::<Day>().is_err())
+    .count()
+}
+
+fn resolve_target(client: &dyn Client, target: &EntryTarget, include_archived: bool) -> Result<(Customer, Project, Service), AcariError> {
+  resolve_target_with(client, target, include_archived, true)
+}
+
+/// Resolve the names of a target, `ask` whether the user may pick missing or ambiguous ones (on a terminal).
+fn resolve_target_with(client: &dyn Client, target: &EntryTarget, include_archived: bool, ask: bool) -> Result<(Customer, Project, Service), AcariError> {
+  let customer = find_customer(client, target.customer.as_deref(), include_archived, ask)?;
+  let project = find_project(client, &customer.id, target.project.as_deref(), include_archived, ask)?;
+  let service = find_service(client, &project.id, target.service.as_deref(), include_archived, ask)?;
+
+  Ok((customer, project, service))
+}
 
-  customers
-    .into_iter()
-    .find(|c| c.name == customer_name)
-    .ok_or_else(|| user_error!("No customer with name: {}", customer_name))
+/// Find a customer by name, or let the user pick one if no name is given.
+fn find_customer(client: &dyn Client, maybe_customer_name: Option<&str>, include_archived: bool, ask: bool) -> Result<Customer, AcariError> {
+  find_match("customer", maybe_customer_name, client.get_customers()?, include_archived, ask)
 }
 
-fn find_project(client: &dyn Client, customer_id: &CustomerId, project_name: &str) -> Result<Project, AcariError> {
-  let projects = client.get_projects()?;
+fn find_project(
+  client: &dyn Client,
+  customer_id: &CustomerId,
+  maybe_project_name: Option<&str>,
+  include_archived: bool,
+  ask: bool,
+) -> Result<Project, AcariError> {
+  let mut projects = client.get_projects()?;
 
-  projects
-    .into_iter()
-    .find(|p| p.name == project_name && p.customer_id.eq(customer_id))
-    .ok_or_else(|| user_error!("No project with name: {}", project_name))
+  projects.retain(|p| p.customer_id.eq(customer_id));
+
+  find_match("project", maybe_project_name, projects, include_archived, ask)
 }
 
-fn find_service(client: &dyn Client, project_id: &ProjectId, service_name: &str) -> Result<Service, AcariError> {
-  let services = client.get_services(project_id)?;
+fn find_service(
+  client: &dyn Client,
+  project_id: &ProjectId,
+  maybe_service_name: Option<&str>,
+  include_archived: bool,
+  ask: bool,
+) -> Result<Service, AcariError> {
+  let service_name = match maybe_service_name {
+    Some(service_name) => service_name,
+    None => return find_match("service", None, client.get_services(project_id)?, include_archived, ask),
+  };
+  // Let the backend search first (which might be cheaper), fuzzy matches require all services though
+  let services = match client.find_services(project_id, service_name)? {
+    services if services.is_empty() => client.get_services(project_id)?,
+    services => services,
+  };
+
+  find_match("service", Some(service_name), services, include_archived, ask)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::LocalConfig;
+
+  fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(ToString::to_string).collect()
+  }
+
+  fn target(customer: Option<&str>, project: Option<&str>, service: Option<&str>) -> EntryTarget {
+    EntryTarget {
+      customer: customer.map(ToString::to_string),
+      project: project.map(ToString::to_string),
+      service: service.map(ToString::to_string),
+      note: None,
+    }
+  }
+
+  #[test]
+  fn test_split_target() -> Result<(), AcariError> {
+    let mut config = Config::default();
+    config.aliases.insert(
+      "web".to_string(),
+      Alias {
+        customer: "ACME".to_string(),
+        project: "Website".to_string(),
+        service: "Development".to_string(),
+        note: None,
+      },
+    );
+
+    let full = args(&["ACME", "Website", "Development", "1:00"]);
+    let (split, remaining) = split_target(&config, &full, 1, 2)?;
+    assert_eq!(split, target(Some("ACME"), Some("Website"), Some("Development")));
+    assert_eq!(remaining, &full[3..]);
+
+    let alias = args(&["@web", "1:00", "yesterday"]);
+    let (split, remaining) = split_target(&config, &alias, 1, 2)?;
+    assert_eq!(split, target(Some("ACME"), Some("Website"), Some("Development")));
+    assert_eq!(remaining, &alias[1..]);
+    assert!(split_target(&config, &args(&["@unknown"]), 0, 1).is_err());
+    assert!(split_target(&config, &args(&["@web", "1:00", "yesterday", "more"]), 1, 2).is_err());
+
+    config.local = LocalConfig {
+      customer: Some("ACME".to_string()),
+      project: Some("Website".to_string()),
+      service: Some("Development".to_string()),
+      ..Default::default()
+    };
+    let defaults = args(&["1:00", "yesterday"]);
+    let (split, remaining) = split_target(&config, &defaults, 1, 2)?;
+    assert_eq!(split, target(Some("ACME"), Some("Website"), Some("Development")));
+    assert_eq!(remaining, &defaults[..]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_split_partial_target() -> Result<(), AcariError> {
+    let config = Config {
+      local: LocalConfig {
+        project: Some("Website".to_string()),
+        service: Some("Development".to_string()),
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+
+    let start = args(&["Globex"]);
+    let (split, remaining) = split_target(&config, &start, 0, 1)?;
+    assert_eq!(split, target(Some("Globex"), Some("Website"), Some("Development")));
+    assert!(remaining.is_empty());
+
+    let add = args(&["Globex", "1:00"]);
+    let (split, remaining) = split_target(&config, &add, 1, 2)?;
+    assert_eq!(split, target(Some("Globex"), Some("Website"), Some("Development")));
+    assert_eq!(remaining, &add[1..]);
+
+    let add_with_day = args(&["Globex", "1:00", "yesterday"]);
+    let (split, remaining) = split_target(&config, &add_with_day, 1, 2)?;
+    assert_eq!(split, target(Some("Globex"), Some("Website"), Some("Development")));
+    assert_eq!(remaining, &add_with_day[1..]);
+
+    let add_with_project = args(&["Globex", "Intranet", "1:00"]);
+    let (split, remaining) = split_target(&config, &add_with_project, 1, 2)?;
+    assert_eq!(split, target(Some("Globex"), Some("Intranet"), Some("Development")));
+    assert_eq!(remaining, &add_with_project[2..]);
+
+    Ok(())
+  }
 
-  services
-    .into_iter()
-    .find(|s| s.name == service_name)
-    .ok_or_else(|| user_error!("No service with name: {}", service_name))
+  #[test]
+  fn test_leading_names() {
+    assert_eq!(leading_names(&args(&["0:30"])), 0);
+    assert_eq!(leading_names(&args(&["1:30", "yesterday"])), 0);
+    assert_eq!(leading_names(&args(&["ACME"])), 1);
+    assert_eq!(leading_names(&args(&["ACME", "Website", "1:30", "2024-05-06"])), 2);
+    assert_eq!(leading_names(&args(&["ACME", "Website", "Development", "Extra"])), 3);
+  }
 }
@@ -0,0 +1,65 @@
+use acari_lib::AcariError;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Whether the user can be asked questions, i.e. stdin and stderr are a terminal.
+pub fn is_interactive() -> bool {
+  io::stdin().is_terminal() && io::stderr().is_terminal()
+}
+
+/// Let the user choose one of the options by number, `None` if nothing was chosen.
+///
+/// Any input that is not a number filters the options (case-insensitive).
+pub fn pick<S: AsRef<str>>(title: &str, options: &[S]) -> Result<Option<usize>, AcariError> {
+  let mut stderr = io::stderr();
+  let mut filter = String::new();
+
+  loop {
+    let visible: Vec<usize> = options
+      .iter()
+      .enumerate()
+      .filter(|(_, option)| option.as_ref().to_lowercase().contains(&filter))
+      .map(|(i, _)| i)
+      .collect();
+
+    if visible.is_empty() {
+      writeln!(stderr, "Nothing matches '{}'", filter)?;
+      filter.clear();
+      continue;
+    }
+    if filter.is_empty() {
+      writeln!(stderr, "{}", title)?;
+    } else {
+      writeln!(stderr, "{} (filter: {})", title, filter)?;
+    }
+    for (n, i) in visible.iter().enumerate() {
+      writeln!(stderr, "{:>3}) {}", n + 1, options[*i].as_ref())?;
+    }
+    write!(stderr, "Select [1-{}] or type to filter (empty to abort): ", visible.len())?;
+    stderr.flush()?;
+
+    let mut input = String::new();
+    if io::stdin().lock().read_line(&mut input)? == 0 {
+      return Ok(None);
+    }
+    let input = input.trim();
+    if input.is_empty() {
+      return Ok(None);
+    }
+    match input.parse::<usize>() {
+      Ok(n) if n >= 1 && n <= visible.len() => return Ok(Some(visible[n - 1])),
+      _ => filter = input.to_lowercase(),
+    }
+  }
+}
+
+/// Ask a yes/no question, anything but `y` or `yes` counts as no.
+pub fn confirm(question: &str) -> Result<bool, AcariError> {
+  let mut stderr = io::stderr();
+  write!(stderr, "{} (y/N): ", question)?;
+  stderr.flush()?;
+
+  let mut input = String::new();
+  io::stdin().lock().read_line(&mut input)?;
+
+  Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,171 @@
+use super::{InitCmd, OutputFormat};
+use crate::config::{Config, Profile};
+use acari_lib::{user_error, AcariError};
+use clap::{Args, Subcommand};
+use prettytable::{format, row, Table};
+use serde_json::json;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct ProfileCmd {
+  #[clap(subcommand)]
+  subcommand: ProfileSubCommand,
+}
+
+#[derive(Debug, Subcommand, PartialEq, Eq)]
+enum ProfileSubCommand {
+  #[clap(about = "Add a new profile")]
+  Add {
+    #[clap(help = "Profile name")]
+    name: String,
+    #[clap(
+      long,
+      help = "Move the top level settings into the new profile (instead of asking for them)",
+      conflicts_with_all = ["backend", "domain", "token_source", "yes", "skip_check"]
+    )]
+    from_default: bool,
+    #[clap(flatten)]
+    init: InitCmd,
+  },
+  #[clap(about = "Remove a profile")]
+  Remove {
+    #[clap(help = "Profile name")]
+    name: String,
+  },
+  #[clap(about = "Rename a profile")]
+  Rename {
+    #[clap(help = "Current profile name")]
+    from: String,
+    #[clap(help = "New profile name")]
+    to: String,
+  },
+  #[clap(about = "Show or set the profile used if none is selected")]
+  Default {
+    #[clap(help = "Profile name")]
+    name: Option<String>,
+    #[clap(long, help = "Use the top level settings again", conflicts_with = "name")]
+    unset: bool,
+  },
+  #[clap(about = "Show the settings of a profile (default: the selected one)")]
+  Show {
+    #[clap(help = "Profile name")]
+    name: Option<String>,
+  },
+}
+
+impl ProfileCmd {
+  pub fn run(&self, maybe_config: Option<Config>, maybe_profile: &Option<String>, output_format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    if let ProfileSubCommand::Add {
+      name,
+      init,
+      from_default: false,
+    } = &self.subcommand
+    {
+      if maybe_config.as_ref().map(|config| config.profiles.contains_key(name)).unwrap_or(false) {
+        return Err(user_error!("Profile {} already exists", name).into());
+      }
+      return init.run(maybe_config, &Some(name.to_string()));
+    }
+    let mut config = maybe_config.ok_or_else(|| user_error!("Missing configuration, run init first"))?;
+
+    match &self.subcommand {
+      ProfileSubCommand::Add { name, .. } => {
+        config.move_default_profile(name)?;
+        config.write()?;
+      }
+      ProfileSubCommand::Remove { name } => {
+        config.profiles.remove(name).ok_or_else(|| user_error!("No such profile: {}", name))?;
+        if config.default_profile.as_ref() == Some(name) {
+          config.default_profile = None;
+        }
+        config.write()?;
+      }
+      ProfileSubCommand::Rename { from, to } => {
+        if config.profiles.contains_key(to) {
+          return Err(user_error!("Profile {} already exists", to).into());
+        }
+        let profile = config.profiles.remove(from).ok_or_else(|| user_error!("No such profile: {}", from))?;
+        config.profiles.insert(to.to_string(), profile);
+        if config.default_profile.as_ref() == Some(from) {
+          config.default_profile = Some(to.to_string());
+        }
+        config.write()?;
+      }
+      ProfileSubCommand::Default { name: None, unset: false } => match &config.default_profile {
+        Some(name) => println!("{}", name),
+        None => println!("No default profile, using top level settings"),
+      },
+      ProfileSubCommand::Default { name, .. } => {
+        if let Some(name) = name {
+          if !config.profiles.contains_key(name) {
+            return Err(user_error!("No such profile: {}", name).into());
+          }
+        }
+        config.default_profile = name.clone();
+        config.write()?;
+      }
+      ProfileSubCommand::Show { name } => {
+        let profile_name = config.profile_name(&name.clone().or_else(|| maybe_profile.clone()));
+        let profile = config.profile(&profile_name)?;
+        let cache_ttl_minutes = profile.cache_ttl_minutes.unwrap_or(config.cache_ttl_minutes);
+
+        match output_format {
+          OutputFormat::Pretty => print_pretty(&profile_name, &profile, cache_ttl_minutes),
+          OutputFormat::Json => print_json(&profile_name, &profile, cache_ttl_minutes)?,
+          OutputFormat::Flat => print_flat(&profile_name, &profile),
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+fn print_pretty(profile_name: &Option<String>, profile: &Profile, cache_ttl_minutes: u64) {
+  let mut profile_table = Table::new();
+  profile_table.set_format(*format::consts::FORMAT_CLEAN);
+
+  profile_table.add_row(row!["Name", profile_name.as_deref().unwrap_or("(top level)")]);
+  profile_table.add_row(row!["Backend", format!("{:?}", profile.client)]);
+  profile_table.add_row(row!["Domain", profile.domain]);
+  profile_table.add_row(row!["Token", profile.credentials.describe()]);
+  profile_table.add_row(row!["Cache TTL (minutes)", cache_ttl_minutes]);
+  if let Some(base_url) = &profile.connection.base_url {
+    profile_table.add_row(row!["Base url", base_url]);
+  }
+  if let Some(proxy) = &profile.connection.proxy {
+    profile_table.add_row(row!["Proxy", proxy]);
+  }
+  for ca_certificate in &profile.connection.ca_certificates {
+    profile_table.add_row(row!["CA certificate", ca_certificate.to_string_lossy()]);
+  }
+  if let Some(user_agent) = &profile.connection.user_agent {
+    profile_table.add_row(row!["User agent", user_agent]);
+  }
+  if let Some(seconds) = profile.connection.connect_timeout_seconds {
+    profile_table.add_row(row!["Connect timeout (seconds)", seconds]);
+  }
+  if let Some(seconds) = profile.connection.timeout_seconds {
+    profile_table.add_row(row!["Request timeout (seconds)", seconds]);
+  }
+  profile_table.printstd();
+}
+
+fn print_json(profile_name: &Option<String>, profile: &Profile, cache_ttl_minutes: u64) -> Result<(), Box<dyn std::error::Error>> {
+  println!(
+    "{}",
+    serde_json::to_string_pretty(&json!({
+      "name": profile_name,
+      "client": profile.client,
+      "domain": profile.domain,
+      "token": profile.credentials.describe(),
+      "cache_ttl_minutes": cache_ttl_minutes,
+      "connection": profile.connection,
+    }))?
+  );
+
+  Ok(())
+}
+
+fn print_flat(profile_name: &Option<String>, profile: &Profile) {
+  println!("{}\t{:?}\t{}", profile_name.as_deref().unwrap_or_default(), profile.client, profile.domain);
+}
@@ -0,0 +1,419 @@
+use super::apply::Action;
+use super::timesheet::TimesheetLine;
+use super::{entries, resolve_target, EntryTarget, OutputFormat};
+use crate::config::{Config, Frequency, RecurringRule};
+use acari_lib::{user_error, AcariError, Client, DateSpan, Project, Service, TimeEntry};
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::{Args, Subcommand};
+use prettytable::{format, row, Table};
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct RecurringCmd {
+  #[clap(subcommand)]
+  subcommand: RecurringSubCommand,
+}
+
+#[derive(Debug, Subcommand, PartialEq, Eq)]
+enum RecurringSubCommand {
+  #[clap(about = "Create the missing occurrences of all recurring entries in a date span")]
+  Apply {
+    #[clap(help = "Date span to apply\n(today, yesterday, this-week, last-week,\n this-month, last-month, yyyy-mm-dd, yyyy-mm-dd/yyyy-mm-dd)")]
+    span: DateSpan,
+    #[clap(long, help = "Only show what would be created")]
+    dry_run: bool,
+    #[clap(long, help = "Include archived customers, projects and services")]
+    archived: bool,
+  },
+  #[clap(about = "List all recurring entries")]
+  List,
+}
+
+impl RecurringCmd {
+  pub fn run(&self, client: &dyn Client, config: &Config, output_format: OutputFormat) -> Result<(), AcariError> {
+    match &self.subcommand {
+      RecurringSubCommand::Apply { span, dry_run, archived } => {
+        let existing = client.get_time_entries(*span)?;
+        let actions = plan(client, &config.recurring, span.date_range(), existing, *archived)?;
+
+        if actions.is_empty() {
+          println!("Nothing to create");
+          return Ok(());
+        }
+        for action in &actions {
+          println!("{}", action);
+        }
+        if *dry_run {
+          return Ok(());
+        }
+        for action in actions {
+          action.apply(client)?;
+        }
+
+        entries(client, output_format, *span)?;
+      }
+      RecurringSubCommand::List => match output_format {
+        OutputFormat::Pretty => print_pretty(&config.recurring),
+        OutputFormat::Json => print_json(&config.recurring)?,
+        OutputFormat::Flat => print_flat(&config.recurring),
+      },
+    }
+
+    Ok(())
+  }
+}
+
+/// Creations of all occurrences in the range that do not have a matching entry yet.
+///
+/// An entry matches an occurrence if it is on the same day with the same project, service and note,
+/// its time does not matter so that adjusted entries are left alone. If the backend keeps a single
+/// entry per service and day any entry on it matches, and occurrences on the same one are added up.
+/// Rules are only resolved once they occur, so that outdated rules do not get in the way.
+pub(crate) fn plan(
+  client: &dyn Client,
+  rules: &[RecurringRule],
+  (from, to): (NaiveDate, NaiveDate),
+  mut existing: Vec<TimeEntry>,
+  include_archived: bool,
+) -> Result<Vec<Action>, AcariError> {
+  let single_entry_per_day = !client.capabilities().multiple_entries_per_day;
+  let mut resolved: Vec<Option<(Project, Service)>> = vec![None; rules.len()];
+  existing.sort_by_key(|e| e.created_at);
+
+  let mut actions = vec![];
+  for date in from.iter_days().take_while(|date| *date <= to) {
+    for (rule, target) in rules.iter().zip(resolved.iter_mut()) {
+      if !occurs_on(rule, date)? {
+        continue;
+      }
+      let (project, service) = match target {
+        Some(target) => target,
+        None => target.insert(resolve_rule(client, rule, include_archived)?),
+      };
+      let note = rule.note.clone().unwrap_or_default();
+      let same_day = |e: &TimeEntry| e.date_at == date && e.project_id == project.id && e.service_id == service.id;
+
+      if single_entry_per_day {
+        if existing.iter().any(same_day) {
+          continue;
+        }
+        let planned = actions.iter_mut().find_map(|action| match action {
+          Action::Create { line, project: p, service: s } if line.date == date && p.id == project.id && s.id == service.id => Some(line),
+          _ => None,
+        });
+        if let Some(line) = planned {
+          line.minutes += rule.minutes;
+          line.note = match (line.note.is_empty(), note.is_empty()) {
+            (_, true) => line.note.clone(),
+            (true, false) => note,
+            (false, false) => format!("{}; {}", line.note, note),
+          };
+          continue;
+        }
+      } else if let Some(index) = existing.iter().position(|e| same_day(e) && e.note == note) {
+        existing.remove(index);
+        continue;
+      }
+
+      actions.push(Action::Create {
+        line: TimesheetLine {
+          date,
+          minutes: rule.minutes,
+          customer: rule.customer.clone(),
+          project: rule.project.clone(),
+          service: rule.service.clone(),
+          note,
+        },
+        project: project.clone(),
+        service: service.clone(),
+      });
+    }
+  }
+
+  Ok(actions)
+}
+
+fn resolve_rule(client: &dyn Client, rule: &RecurringRule, include_archived: bool) -> Result<(Project, Service), AcariError> {
+  let target = EntryTarget {
+    customer: Some(rule.customer.clone()),
+    project: Some(rule.project.clone()),
+    service: Some(rule.service.clone()),
+    note: None,
+  };
+  let (_, project, service) = resolve_target(client, &target, include_archived)?;
+
+  Ok((project, service))
+}
+
+/// Check if a rule has an occurrence on a day.
+///
+/// Weekly rules default to the weekday of `start` and monthly rules to its day of month, an
+/// `interval` larger than one is counted from `start` and requires it as well.
+pub(crate) fn occurs_on(rule: &RecurringRule, date: NaiveDate) -> Result<bool, AcariError> {
+  if rule.start.map(|start| date < start).unwrap_or(false) || rule.until.map(|until| date > until).unwrap_or(false) {
+    return Ok(false);
+  }
+  let interval = i64::from(rule.interval.unwrap_or(1));
+  if interval < 1 {
+    return Err(user_error!("Interval of recurring entry for {} must be at least 1", describe_target(rule)));
+  }
+  let periods_since_start = |start: NaiveDate| match rule.freq {
+    Frequency::Daily => (date - start).num_days(),
+    Frequency::Weekly => (week_start(date) - week_start(start)).num_weeks(),
+    Frequency::Monthly => i64::from(date.year() - start.year()) * 12 + i64::from(date.month()) - i64::from(start.month()),
+  };
+  let in_interval = match rule.start {
+    _ if interval == 1 => true,
+    Some(start) => periods_since_start(start) % interval == 0,
+    None => return Err(user_error!("Recurring entry for {} has an interval but no start", describe_target(rule))),
+  };
+
+  let on_day = match rule.freq {
+    Frequency::Daily => rule.weekdays.is_empty() || rule.weekdays.contains(&date.weekday()),
+    Frequency::Weekly if !rule.weekdays.is_empty() => rule.weekdays.contains(&date.weekday()),
+    Frequency::Weekly => match rule.start {
+      Some(start) => start.weekday() == date.weekday(),
+      None => return Err(user_error!("Weekly recurring entry for {} needs weekdays or a start", describe_target(rule))),
+    },
+    Frequency::Monthly if !rule.month_days.is_empty() => rule.month_days.contains(&date.day()),
+    Frequency::Monthly => match rule.start {
+      Some(start) => start.day() == date.day(),
+      None => return Err(user_error!("Monthly recurring entry for {} needs month_days or a start", describe_target(rule))),
+    },
+  };
+
+  Ok(in_interval && on_day)
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+  date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
+fn describe_target(rule: &RecurringRule) -> String {
+  format!("{} / {} / {}", rule.customer, rule.project, rule.service)
+}
+
+fn describe_schedule(rule: &RecurringRule) -> String {
+  let mut schedule = match (rule.freq, rule.interval.unwrap_or(1)) {
+    (Frequency::Daily, 1) => "daily".to_string(),
+    (Frequency::Weekly, 1) => "weekly".to_string(),
+    (Frequency::Monthly, 1) => "monthly".to_string(),
+    (Frequency::Daily, interval) => format!("every {} days", interval),
+    (Frequency::Weekly, interval) => format!("every {} weeks", interval),
+    (Frequency::Monthly, interval) => format!("every {} months", interval),
+  };
+
+  if !rule.weekdays.is_empty() {
+    let weekdays: Vec<String> = rule.weekdays.iter().map(ToString::to_string).collect();
+    schedule.push_str(&format!(" on {}", weekdays.join(", ")));
+  }
+  if !rule.month_days.is_empty() {
+    let month_days: Vec<String> = rule.month_days.iter().map(ToString::to_string).collect();
+    schedule.push_str(&format!(" on day {}", month_days.join(", ")));
+  }
+  if let Some(start) = rule.start {
+    schedule.push_str(&format!(" from {}", start));
+  }
+  if let Some(until) = rule.until {
+    schedule.push_str(&format!(" until {}", until));
+  }
+  schedule
+}
+
+fn print_pretty(rules: &[RecurringRule]) {
+  let mut rules_table = Table::new();
+  rules_table.set_titles(row!["Schedule", "Time", "Customer", "Project", "Service", "Note"]);
+  rules_table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+  for rule in rules {
+    rules_table.add_row(row![
+      describe_schedule(rule),
+      rule.minutes,
+      rule.customer,
+      rule.project,
+      rule.service,
+      rule.note.as_deref().unwrap_or_default()
+    ]);
+  }
+  rules_table.printstd();
+}
+
+fn print_json(rules: &[RecurringRule]) -> Result<(), AcariError> {
+  println!("{}", serde_json::to_string_pretty(rules)?);
+
+  Ok(())
+}
+
+fn print_flat(rules: &[RecurringRule]) {
+  for rule in rules {
+    println!(
+      "{}\t{}\t{}\t{}\t{}\t{}",
+      describe_schedule(rule),
+      rule.minutes,
+      rule.customer,
+      rule.project,
+      rule.service,
+      rule.note.as_deref().unwrap_or_default()
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::commands::test_support::{date, mock_client};
+  use acari_lib::{Capabilities, Minutes, Mutation, ProjectId, ServiceId};
+  use chrono::Weekday;
+
+  fn rule(freq: Frequency) -> RecurringRule {
+    RecurringRule {
+      customer: "acme".to_string(),
+      project: "web".to_string(),
+      service: "dev".to_string(),
+      minutes: Minutes(15),
+      note: Some("Standup".to_string()),
+      freq,
+      interval: None,
+      weekdays: vec![],
+      month_days: vec![],
+      start: None,
+      until: None,
+    }
+  }
+
+  fn occurrences(rule: &RecurringRule, from: u32, to: u32) -> Result<Vec<u32>, AcariError> {
+    let mut days = vec![];
+    for day in from..=to {
+      if occurs_on(rule, date(day))? {
+        days.push(day);
+      }
+    }
+    Ok(days)
+  }
+
+  #[test]
+  fn test_occurs_on() -> Result<(), AcariError> {
+    // 2024-05-06 is a Monday
+    let workdays = RecurringRule {
+      weekdays: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+      until: Some(date(15)),
+      ..rule(Frequency::Daily)
+    };
+    assert_eq!(occurrences(&workdays, 6, 19)?, vec![6, 7, 8, 9, 10, 13, 14, 15]);
+
+    let biweekly = RecurringRule {
+      interval: Some(2),
+      start: Some(date(2)),
+      ..rule(Frequency::Weekly)
+    };
+    assert_eq!(occurrences(&biweekly, 1, 31)?, vec![2, 16, 30]);
+
+    let monthly = RecurringRule {
+      month_days: vec![1, 15],
+      ..rule(Frequency::Monthly)
+    };
+    assert_eq!(occurrences(&monthly, 1, 31)?, vec![1, 15]);
+
+    assert!(occurs_on(&rule(Frequency::Weekly), date(6)).is_err());
+    assert!(occurs_on(
+      &RecurringRule {
+        interval: Some(2),
+        ..rule(Frequency::Daily)
+      },
+      date(6)
+    )
+    .is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_plan_is_idempotent() -> Result<(), AcariError> {
+    let client = mock_client();
+    let rules = vec![
+      RecurringRule {
+        weekdays: vec![Weekday::Mon, Weekday::Wed],
+        ..rule(Frequency::Daily)
+      },
+      RecurringRule {
+        minutes: Minutes(60),
+        note: Some("Jour fixe".to_string()),
+        weekdays: vec![Weekday::Wed],
+        ..rule(Frequency::Weekly)
+      },
+    ];
+
+    let actions = plan(&client, &rules, (date(6), date(12)), vec![], false)?;
+    assert_eq!(actions.len(), 3);
+    for action in actions {
+      action.apply(&client)?;
+    }
+    assert_eq!(plan(&client, &rules, (date(6), date(12)), client.time_entries(), false)?, vec![]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_plan_single_entry_per_day() -> Result<(), AcariError> {
+    let client = mock_client().with_capabilities(Capabilities {
+      multiple_entries_per_day: false,
+      ..mock_client().capabilities()
+    });
+    let rules = vec![
+      RecurringRule {
+        weekdays: vec![Weekday::Mon, Weekday::Wed],
+        ..rule(Frequency::Daily)
+      },
+      RecurringRule {
+        minutes: Minutes(60),
+        note: Some("Jour fixe".to_string()),
+        weekdays: vec![Weekday::Wed],
+        ..rule(Frequency::Weekly)
+      },
+    ];
+
+    for _ in 0..2 {
+      for action in plan(&client, &rules, (date(6), date(12)), client.time_entries(), false)? {
+        action.apply(&client)?;
+      }
+    }
+    assert_eq!(
+      client.mutations(),
+      vec![
+        Mutation::CreateTimeEntry {
+          date: date(6),
+          project_id: ProjectId::Num(10),
+          service_id: ServiceId::Num(100),
+          minutes: Minutes(15),
+          note: Some("Standup".to_string()),
+        },
+        Mutation::CreateTimeEntry {
+          date: date(8),
+          project_id: ProjectId::Num(10),
+          service_id: ServiceId::Num(100),
+          minutes: Minutes(75),
+          note: Some("Standup; Jour fixe".to_string()),
+        },
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_plan_skips_rules_outside_of_span() -> Result<(), AcariError> {
+    let client = mock_client();
+    let rules = vec![
+      RecurringRule {
+        customer: "Gone".to_string(),
+        until: Some(date(1)),
+        ..rule(Frequency::Daily)
+      },
+      rule(Frequency::Daily),
+    ];
+
+    assert_eq!(plan(&client, &rules, (date(6), date(6)), vec![], false)?.len(), 1);
+    assert!(plan(&client, &rules, (date(1), date(1)), vec![], false).is_err());
+
+    Ok(())
+  }
+}
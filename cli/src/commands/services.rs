@@ -3,35 +3,97 @@ use super::{find_customer, find_project};
 use acari_lib::{AcariError, Client, Service};
 use clap::Args;
 use itertools::Itertools;
-use prettytable::table;
+use prettytable::{format, row, table, Table};
 
 #[derive(Debug, Args, PartialEq, Eq)]
 pub struct ServicesCommand {
-  #[clap(help = "Customer name")]
-  customer: String,
-  #[clap(help = "Project name")]
-  project: String,
+  #[clap(help = "Customer name (picked interactively if omitted)")]
+  customer: Option<String>,
+  #[clap(help = "Project name (picked interactively if omitted)")]
+  project: Option<String>,
+  #[clap(long, help = "Group services by section")]
+  section: bool,
+  #[clap(long, help = "Include archived customers and projects")]
+  archived: bool,
 }
 
 impl ServicesCommand {
   pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
-    let customer = find_customer(client, &self.customer)?;
-    let project = find_project(client, &customer.id, &self.project)?;
+    let customer = find_customer(client, self.customer.as_deref(), self.archived, true)?;
+    let project = find_project(client, &customer.id, self.project.as_deref(), self.archived, true)?;
     let mut services = client.get_services(&project.id)?;
 
     services.sort_by(|s1, s2| s1.name.cmp(&s2.name));
 
-    match output_format {
-      OutputFormat::Pretty => print_pretty(services),
-      OutputFormat::Json => print_json(services)?,
-      OutputFormat::Flat => print_flat(services),
+    if self.section {
+      services.sort_by(|s1, s2| s1.section.cmp(&s2.section));
+
+      let grouped: Vec<(Option<&str>, Vec<&Service>)> = services
+        .iter()
+        .chunk_by(|s| s.section.as_deref())
+        .into_iter()
+        .map(|(section, group)| (section, group.collect()))
+        .collect();
+
+      match output_format {
+        OutputFormat::Pretty => print_sections_pretty(grouped),
+        OutputFormat::Json => print_json(services)?,
+        OutputFormat::Flat => print_sections_flat(grouped),
+      }
+    } else {
+      match output_format {
+        OutputFormat::Pretty => print_pretty(services, client.capabilities().billable),
+        OutputFormat::Json => print_json(services)?,
+        OutputFormat::Flat => print_flat(services),
+      }
     }
 
     Ok(())
   }
 }
 
-fn print_pretty(services: Vec<Service>) {
+fn print_sections_pretty(sections: Vec<(Option<&str>, Vec<&Service>)>) {
+  let mut sections_table = Table::new();
+  sections_table.set_titles(row!["Section", "Service", "Estimate", "Spent"]);
+  sections_table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+  for (section, group) in sections {
+    sections_table.add_row(row![bFc -> section.unwrap_or("No section"), "", "", ""]);
+    for service in group {
+      let estimate = service.estimate.map(|e| e.to_string()).unwrap_or_default();
+      let time_spent = service.time_spent.map(|t| t.to_string()).unwrap_or_default();
+
+      if service.archived {
+        sections_table.add_row(row![FY => "", service.name, estimate, time_spent]);
+      } else {
+        sections_table.add_row(row!["", service.name, estimate, time_spent]);
+      }
+    }
+  }
+  sections_table.printstd();
+}
+
+fn print_sections_flat(sections: Vec<(Option<&str>, Vec<&Service>)>) {
+  for (section, group) in sections {
+    for service in group {
+      if !service.archived {
+        println!("{}\t{}", section.unwrap_or_default(), service.name);
+      }
+    }
+  }
+}
+
+fn print_pretty(services: Vec<Service>, billable: bool) {
+  if !billable {
+    let service_table = table!(
+      ["Services"],
+      [services.iter().filter(|s| !s.archived).map(|s| &s.name).join("\n")],
+      ["Archived"],
+      [services.iter().filter(|s| s.archived).map(|s| &s.name).join("\n")]
+    );
+    service_table.printstd();
+    return;
+  }
   let service_table = table!(
     ["Billable services"],
     [services.iter().filter(|s| s.billable && !s.archived).map(|s| &s.name).join("\n")],
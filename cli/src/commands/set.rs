@@ -1,41 +1,48 @@
 use super::OutputFormat;
-use super::{entries, find_customer, find_project, find_service};
-use acari_lib::{AcariError, Client, Day, Minutes};
+use super::{check_note_support, entries, resolve_target, split_target};
+use crate::config::Config;
+use acari_lib::{user_error, AcariError, Client, Day, Minutes};
 use clap::Args;
 
 #[derive(Debug, Args, PartialEq, Eq)]
 pub struct SetCmd {
-  #[clap(help = "Customer name")]
-  customer: String,
-  #[clap(help = "Project name")]
-  project: String,
-  #[clap(help = "Service name")]
-  service: String,
-  #[clap(help = "Time (minutes or hh:mm)")]
-  time: Minutes,
-  #[clap(help = "Date", default_value = "today")]
-  day: Day,
+  #[clap(
+    value_name = "ARGS",
+    required = true,
+    num_args = 1..=5,
+    help = "[@<alias> | <customer> <project> <service>] <time (minutes or hh:mm)> [<date>] (customer, project and service may be omitted if set in a .acari.toml)"
+  )]
+  args: Vec<String>,
   #[clap(short, long, help = "Optional note")]
   note: Option<String>,
+  #[clap(long, help = "Include archived customers, projects and services")]
+  archived: bool,
 }
 
 impl SetCmd {
-  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
-    let customer = find_customer(client, &self.customer)?;
-    let project = find_project(client, &customer.id, &self.project)?;
-    let service = find_service(client, &project.id, &self.service)?;
-    let date = self.day.as_date();
+  pub fn run(&self, client: &dyn Client, config: &Config, output_format: OutputFormat) -> Result<(), AcariError> {
+    let (target, remaining) = split_target(config, &self.args, 1, 2)?;
+    let note = self.note.clone().or_else(|| target.note.clone());
+    check_note_support(client, &note)?;
+    let time: Minutes = remaining[0].parse()?;
+    let day: Day = remaining.get(1).map(|d| d.parse()).transpose()?.unwrap_or(Day::Today);
+    let (customer, project, service) = resolve_target(client, &target, self.archived)?;
+    let date = day.as_date();
     let mut time_entries = client.get_time_entries(date.into())?;
 
     time_entries.retain(|e| e.date_at == date && e.customer_id.eq(&customer.id) && e.project_id.eq(&project.id) && e.service_id.eq(&service.id));
 
+    if client.capabilities().locking && time_entries.iter().any(|e| e.locked) {
+      return Err(user_error!("Time entries of {} on {} are locked", service.name, date));
+    }
+
     if let Some(first) = time_entries.first() {
-      client.update_time_entry(&first.id, self.time, self.note.clone())?;
+      client.update_time_entry(&first.id, time, note)?;
       for remaining in &time_entries[1..] {
         client.delete_time_entry(&remaining.id)?;
       }
     } else {
-      client.create_time_entry(self.day, &project.id, &service.id, self.time, self.note.clone())?;
+      client.create_time_entry(day, &project.id, &service.id, time, note)?;
     }
 
     entries(client, output_format, date.into())
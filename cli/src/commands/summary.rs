@@ -0,0 +1,262 @@
+use super::{current_minutes, OutputFormat};
+use acari_lib::{AcariError, Client, DateSpan, Minutes, TimeEntry};
+use chrono::Datelike;
+use clap::{Args, ValueEnum};
+use prettytable::{format, Cell, Row, Table};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct SummaryCmd {
+  #[clap(help = "Date span to summarize\n(today, yesterday, this-week, last-week,\n this-month, last-month, yyyy-mm-dd, yyyy-mm-dd/yyyy-mm-dd)")]
+  span: DateSpan,
+  #[clap(
+    long,
+    value_enum,
+    value_delimiter = ',',
+    default_value = "customer,project",
+    help = "Nested grouping of the totals"
+  )]
+  by: Vec<GroupBy>,
+}
+
+#[derive(ValueEnum, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GroupBy {
+  Customer,
+  Project,
+  Service,
+  Note,
+  Week,
+}
+
+impl GroupBy {
+  fn key(self, entry: &TimeEntry) -> String {
+    match self {
+      GroupBy::Customer => entry.customer_name.clone(),
+      GroupBy::Project => entry.project_name.clone(),
+      GroupBy::Service => entry.service_name.clone(),
+      GroupBy::Note if entry.note.is_empty() => "(no note)".to_string(),
+      GroupBy::Note => entry.note.clone(),
+      GroupBy::Week => {
+        let week = entry.date_at.iso_week();
+        format!("{}-W{:02}", week.year(), week.week())
+      }
+    }
+  }
+
+  fn name(self) -> &'static str {
+    match self {
+      GroupBy::Customer => "customer",
+      GroupBy::Project => "project",
+      GroupBy::Service => "service",
+      GroupBy::Note => "note",
+      GroupBy::Week => "week",
+    }
+  }
+}
+
+impl SummaryCmd {
+  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
+    let (time_entries, tracker) = client.get_time_entries_with_tracker(self.span)?;
+    let entries: Vec<(&TimeEntry, Minutes)> = time_entries
+      .iter()
+      .map(|entry| (entry, current_minutes(entry, &tracker.tracking_time_entry)))
+      .collect();
+    let summary = Group::new("Total".to_string(), &entries, &self.by);
+    let billable = client.capabilities().billable;
+
+    match output_format {
+      OutputFormat::Pretty => print_pretty(&summary, billable),
+      OutputFormat::Json => print_json(&summary, &self.by, billable)?,
+      OutputFormat::Flat => print_flat(&summary, billable),
+    }
+
+    Ok(())
+  }
+}
+
+/// Totals of a group of time entries and its sub groups.
+#[derive(Debug, PartialEq, Eq)]
+struct Group {
+  name: String,
+  minutes: Minutes,
+  billable: Minutes,
+  groups: Vec<Group>,
+}
+
+impl Group {
+  /// Group entries (with their current minutes) by the first key, and the sub groups by the remaining ones.
+  fn new(name: String, entries: &[(&TimeEntry, Minutes)], by: &[GroupBy]) -> Group {
+    let minutes = entries.iter().map(|(_, minutes)| *minutes).sum();
+    let billable = entries.iter().filter(|(entry, _)| entry.billable).map(|(_, minutes)| *minutes).sum();
+    let groups = match by.split_first() {
+      Some((first, rest)) => {
+        let mut grouped: BTreeMap<String, Vec<(&TimeEntry, Minutes)>> = BTreeMap::new();
+        for (entry, minutes) in entries {
+          grouped.entry(first.key(entry)).or_default().push((entry, *minutes));
+        }
+        grouped.into_iter().map(|(key, group)| Group::new(key, &group, rest)).collect()
+      }
+      None => vec![],
+    };
+
+    Group {
+      name,
+      minutes,
+      billable,
+      groups,
+    }
+  }
+
+  fn non_billable(&self) -> Minutes {
+    Minutes(self.minutes.0 - self.billable.0)
+  }
+
+  fn percentage_of(&self, total: Minutes) -> f64 {
+    if total.0 == 0 {
+      0.0
+    } else {
+      f64::from(self.minutes.0) * 100.0 / f64::from(total.0)
+    }
+  }
+}
+
+/// Time, percentage and (if the backend knows about it) the billable split of a group.
+fn columns(group: &Group, total: Minutes, billable: bool) -> Vec<String> {
+  let mut columns = vec![group.minutes.to_string(), format!("{:.1}", group.percentage_of(total))];
+  if billable {
+    columns.push(group.billable.to_string());
+    columns.push(group.non_billable().to_string());
+  }
+  columns
+}
+
+fn print_pretty(summary: &Group, billable: bool) {
+  if summary.groups.is_empty() {
+    println!("No entries found");
+    return;
+  }
+
+  let mut summary_table = Table::new();
+  let mut titles = vec!["Group", "Time", "%"];
+  if billable {
+    titles.extend(&["Billable", "Non-billable"]);
+  }
+  let width = titles.len();
+  summary_table.set_titles(Row::new(titles.into_iter().map(Cell::new).collect()));
+  summary_table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+  fn add_rows(table: &mut Table, group: &Group, depth: usize, total: Minutes, billable: bool) {
+    let mut cells = vec![format!("{}{}", "  ".repeat(depth), group.name)];
+    cells.extend(columns(group, total, billable));
+    let style = if depth == 0 { "bFc" } else { "" };
+    table.add_row(Row::new(cells.iter().map(|cell| Cell::new(cell).style_spec(style)).collect()));
+    for sub_group in &group.groups {
+      add_rows(table, sub_group, depth + 1, total, billable);
+    }
+  }
+  for group in &summary.groups {
+    add_rows(&mut summary_table, group, 0, summary.minutes, billable);
+  }
+  let mut separator = vec!["-----", "-----"];
+  separator.resize(width, "");
+  summary_table.add_row(Row::new(separator.into_iter().map(Cell::new).collect()));
+  let mut totals = vec![summary.name.clone()];
+  totals.extend(columns(summary, summary.minutes, billable));
+  summary_table.add_row(Row::new(totals.iter().map(|cell| Cell::new(cell).style_spec("bFw")).collect()));
+
+  summary_table.printstd();
+}
+
+fn print_json(summary: &Group, by: &[GroupBy], billable: bool) -> Result<(), AcariError> {
+  fn to_json(group: &Group, total: Minutes, billable: bool) -> Value {
+    let mut json_group = json!({
+      "name": group.name,
+      "minutes": group.minutes,
+      "percentage": group.percentage_of(total),
+      "groups": group.groups.iter().map(|sub_group| to_json(sub_group, total, billable)).collect::<Vec<_>>(),
+    });
+    if billable {
+      json_group["billable_minutes"] = json!(group.billable);
+      json_group["non_billable_minutes"] = json!(group.non_billable());
+    }
+    json_group
+  }
+  let mut json_summary = to_json(summary, summary.minutes, billable);
+  json_summary["by"] = json!(by.iter().map(|group_by| group_by.name()).collect::<Vec<_>>());
+
+  println!("{}", serde_json::to_string_pretty(&json_summary)?);
+
+  Ok(())
+}
+
+/// Only the innermost groups, prefixed by the names of all their parents.
+fn print_flat(summary: &Group, billable: bool) {
+  fn print_leaves(group: &Group, path: &mut Vec<String>, total: Minutes, billable: bool) {
+    if group.groups.is_empty() {
+      println!("{}\t{}", path.join("\t"), columns(group, total, billable).join("\t"));
+    }
+    for sub_group in &group.groups {
+      path.push(sub_group.name.clone());
+      print_leaves(sub_group, path, total, billable);
+      path.pop();
+    }
+  }
+  if !summary.groups.is_empty() {
+    print_leaves(summary, &mut vec![], summary.minutes, billable);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::commands::test_support;
+
+  fn entry(day: u32, minutes: u32, customer: &str, project: &str, billable: bool) -> TimeEntry {
+    TimeEntry {
+      customer_name: customer.to_string(),
+      project_name: project.to_string(),
+      billable,
+      ..test_support::entry(u64::from(day), day, minutes)
+    }
+  }
+
+  fn group(name: &str, minutes: u32, billable: u32, groups: Vec<Group>) -> Group {
+    Group {
+      name: name.to_string(),
+      minutes: Minutes(minutes),
+      billable: Minutes(billable),
+      groups,
+    }
+  }
+
+  #[test]
+  fn test_group() {
+    let time_entries = [
+      entry(6, 60, "ACME", "Website", true),
+      entry(7, 30, "Globex", "Internal", false),
+      entry(13, 90, "ACME", "Website", true),
+      entry(14, 60, "ACME", "Shop", false),
+    ];
+    let entries: Vec<(&TimeEntry, Minutes)> = time_entries.iter().map(|e| (e, e.minutes)).collect();
+
+    let summary = Group::new("Total".to_string(), &entries, &[GroupBy::Customer, GroupBy::Project]);
+    assert_eq!(
+      summary,
+      group(
+        "Total",
+        240,
+        150,
+        vec![
+          group("ACME", 210, 150, vec![group("Shop", 60, 0, vec![]), group("Website", 150, 150, vec![])]),
+          group("Globex", 30, 0, vec![group("Internal", 30, 0, vec![])]),
+        ]
+      )
+    );
+    assert_eq!(summary.groups[1].non_billable(), Minutes(30));
+    assert_eq!(summary.groups[0].percentage_of(summary.minutes), 87.5);
+
+    let by_week = Group::new("Total".to_string(), &entries, &[GroupBy::Week]);
+    assert_eq!(by_week.groups, vec![group("2024-W19", 90, 60, vec![]), group("2024-W20", 150, 90, vec![])]);
+  }
+}
@@ -0,0 +1,78 @@
+//! Fixtures shared by the tests of the commands: the customer ACME with its project Website and
+//! service Development, and time entries booked on them in May 2024 (2024-05-06 is a monday).
+
+use acari_lib::{Customer, CustomerId, Minutes, MockClient, Project, ProjectId, Service, ServiceId, TimeEntry, TimeEntryId, UserId};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+pub fn date(day: u32) -> NaiveDate {
+  NaiveDate::from_ymd_opt(2024, 5, day).unwrap()
+}
+
+fn created_at() -> DateTime<Utc> {
+  Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+}
+
+pub fn customer() -> Customer {
+  Customer {
+    id: CustomerId::Num(1),
+    name: "ACME".to_string(),
+    note: "".to_string(),
+    archived: false,
+    created_at: created_at(),
+  }
+}
+
+pub fn project() -> Project {
+  Project {
+    id: ProjectId::Num(10),
+    name: "Website".to_string(),
+    customer_id: CustomerId::Num(1),
+    customer_name: "ACME".to_string(),
+    note: "".to_string(),
+    archived: false,
+    created_at: created_at(),
+  }
+}
+
+pub fn service() -> Service {
+  Service {
+    id: ServiceId::Num(100),
+    name: "Development".to_string(),
+    note: "".to_string(),
+    billable: true,
+    archived: false,
+    section: None,
+    estimate: None,
+    time_spent: None,
+    created_at: created_at(),
+  }
+}
+
+/// Mock client knowing customer, project and service, but no time entries yet.
+pub fn mock_client() -> MockClient {
+  MockClient::new()
+    .with_customer(customer())
+    .with_project(project())
+    .with_service(&ProjectId::Num(10), service())
+}
+
+/// Time entry on ACME / Website / Development, entries with higher ids are created later.
+pub fn entry(id: u64, day: u32, minutes: u32) -> TimeEntry {
+  TimeEntry {
+    id: TimeEntryId::Num(id),
+    date_at: date(day),
+    minutes: Minutes(minutes),
+    customer_id: CustomerId::Num(1),
+    customer_name: "ACME".to_string(),
+    project_id: ProjectId::Num(10),
+    project_name: "Website".to_string(),
+    service_id: ServiceId::Num(100),
+    service_name: "Development".to_string(),
+    user_id: UserId::Num(1),
+    user_name: "Mock User".to_string(),
+    note: "".to_string(),
+    billable: true,
+    locked: false,
+    created_at: Utc.with_ymd_and_hms(2024, 5, day, 12, 0, 0).unwrap() + chrono::Duration::seconds(id as i64),
+  }
+}
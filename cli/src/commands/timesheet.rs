@@ -0,0 +1,255 @@
+use crate::config::Alias;
+use acari_lib::{user_error, AcariError, Minutes, TimeEntry};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// A line of a timesheet file: `<date> <time> <customer>/<project>/<service> ["<note>"]`.
+///
+/// The target may also be an `@alias`. Names containing whitespace have to be quoted, a `/` inside
+/// a name has to be escaped as `\/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimesheetLine {
+  pub date: NaiveDate,
+  pub minutes: Minutes,
+  pub customer: String,
+  pub project: String,
+  pub service: String,
+  pub note: String,
+}
+
+impl TimesheetLine {
+  pub fn from_entry(entry: &TimeEntry) -> TimesheetLine {
+    TimesheetLine {
+      date: entry.date_at,
+      minutes: entry.minutes,
+      customer: entry.customer_name.clone(),
+      project: entry.project_name.clone(),
+      service: entry.service_name.clone(),
+      note: entry.note.clone(),
+    }
+  }
+
+  pub fn render(&self) -> String {
+    let target = [&self.customer, &self.project, &self.service]
+      .iter()
+      .map(|name| escape(name, &['\\', '"', '/']))
+      .collect::<Vec<_>>()
+      .join("/");
+    let mut line = if target.contains(char::is_whitespace) {
+      format!("{} {} \"{}\"", self.date, self.minutes, target)
+    } else {
+      format!("{} {} {}", self.date, self.minutes, target)
+    };
+
+    if !self.note.is_empty() {
+      line.push_str(&format!(" \"{}\"", escape(&self.note, &['\\', '"'])));
+    }
+    line
+  }
+}
+
+/// Parse a timesheet, empty lines and lines starting with `#` are ignored.
+pub fn parse(content: &str, aliases: &BTreeMap<String, Alias>) -> Result<Vec<TimesheetLine>, AcariError> {
+  content
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+    .map(|(index, line)| {
+      parse_line(line, aliases).map_err(|err| match err {
+        AcariError::UserError(message) => user_error!("Line {}: {}", index + 1, message),
+        err => user_error!("Line {}: {}", index + 1, err),
+      })
+    })
+    .collect()
+}
+
+fn parse_line(line: &str, aliases: &BTreeMap<String, Alias>) -> Result<TimesheetLine, AcariError> {
+  let tokens = tokenize(line)?;
+  if tokens.len() < 3 || tokens.len() > 4 {
+    return Err(user_error!("Expected <date> <time> <customer>/<project>/<service> [\"<note>\"]"));
+  }
+  let date = NaiveDate::parse_from_str(&tokens[0], "%Y-%m-%d")?;
+  let minutes: Minutes = tokens[1].parse()?;
+  let note = tokens.get(3).cloned();
+
+  if let Some(alias_name) = tokens[2].strip_prefix('@') {
+    let alias = aliases.get(alias_name).ok_or_else(|| user_error!("No such alias: {}", alias_name))?;
+    return Ok(TimesheetLine {
+      date,
+      minutes,
+      customer: alias.customer.clone(),
+      project: alias.project.clone(),
+      service: alias.service.clone(),
+      note: note.or_else(|| alias.note.clone()).unwrap_or_default(),
+    });
+  }
+
+  let names = split_target(&tokens[2]);
+  if names.len() != 3 || names.iter().any(String::is_empty) {
+    return Err(user_error!("Expected <customer>/<project>/<service> or @<alias>, got: {}", tokens[2]));
+  }
+  let mut names = names.into_iter();
+
+  Ok(TimesheetLine {
+    date,
+    minutes,
+    customer: names.next().unwrap_or_default(),
+    project: names.next().unwrap_or_default(),
+    service: names.next().unwrap_or_default(),
+    note: note.unwrap_or_default(),
+  })
+}
+
+/// Split a line at whitespace, double quotes group words and `\` escapes the next character.
+/// Escapes are kept (apart from escaped quotes) so that the target can be split afterwards.
+fn tokenize(line: &str) -> Result<Vec<String>, AcariError> {
+  let mut tokens = vec![];
+  let mut current: Option<String> = None;
+  let mut quoted = false;
+  let mut chars = line.chars();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => {
+        let escaped = chars.next().ok_or_else(|| user_error!("Dangling \\ at end of line"))?;
+        let token = current.get_or_insert_with(String::new);
+        if escaped != '"' {
+          token.push('\\');
+        }
+        token.push(escaped);
+      }
+      '"' => {
+        quoted = !quoted;
+        current.get_or_insert_with(String::new);
+      }
+      c if c.is_whitespace() && !quoted => tokens.extend(current.take()),
+      c => current.get_or_insert_with(String::new).push(c),
+    }
+  }
+  if quoted {
+    return Err(user_error!("Unterminated quote"));
+  }
+  tokens.extend(current);
+
+  // Only the target may contain escaped slashes, everywhere else the escape is dropped
+  Ok(
+    tokens
+      .into_iter()
+      .enumerate()
+      .map(|(index, token)| if index == 2 { token } else { unescape(&token) })
+      .collect(),
+  )
+}
+
+fn split_target(target: &str) -> Vec<String> {
+  let mut names = vec![String::new()];
+  let mut chars = target.chars();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => names.last_mut().unwrap().extend(chars.next()),
+      '/' => names.push(String::new()),
+      c => names.last_mut().unwrap().push(c),
+    }
+  }
+  names
+}
+
+fn unescape(token: &str) -> String {
+  let mut result = String::new();
+  let mut chars = token.chars();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => result.extend(chars.next()),
+      c => result.push(c),
+    }
+  }
+  result
+}
+
+fn escape(value: &str, special: &[char]) -> String {
+  let mut result = String::new();
+
+  for c in value.chars() {
+    if special.contains(&c) {
+      result.push('\\');
+    }
+    result.push(c);
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn line(customer: &str, project: &str, service: &str, note: &str) -> TimesheetLine {
+    TimesheetLine {
+      date: NaiveDate::from_ymd_opt(2024, 5, 2).unwrap(),
+      minutes: Minutes(90),
+      customer: customer.to_string(),
+      project: project.to_string(),
+      service: service.to_string(),
+      note: note.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_parse() -> Result<(), AcariError> {
+    let mut aliases = BTreeMap::new();
+    aliases.insert(
+      "dev".to_string(),
+      Alias {
+        customer: "ACME GmbH".to_string(),
+        project: "Website".to_string(),
+        service: "Development".to_string(),
+        note: Some("Sprint".to_string()),
+      },
+    );
+    let content = r#"
+# Week 18
+2024-05-02 1:30 acme/website/dev "Fix login"
+2024-05-02 90   "ACME GmbH/Web\/App/Dev ops"
+2024-05-02 1:30 @dev
+2024-05-02 1:30 @dev "Say \"hi\""
+"#;
+
+    assert_eq!(
+      parse(content, &aliases)?,
+      vec![
+        line("acme", "website", "dev", "Fix login"),
+        line("ACME GmbH", "Web/App", "Dev ops", ""),
+        line("ACME GmbH", "Website", "Development", "Sprint"),
+        line("ACME GmbH", "Website", "Development", "Say \"hi\""),
+      ]
+    );
+
+    assert!(parse("2024-05-02 1:30 acme/website", &aliases).is_err());
+    assert!(parse("2024-05-02 1:30 @unknown", &aliases).is_err());
+    assert!(parse("2024-05-02 later acme/website/dev", &aliases).is_err());
+    assert!(parse("2024-05-02 1:30 acme/website/dev \"note", &aliases).is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_render() -> Result<(), AcariError> {
+    let lines = vec![
+      line("acme", "website", "dev", ""),
+      line("ACME GmbH", "Web/App", "Dev ops", "Say \"hi\" # not a comment"),
+    ];
+    let rendered: Vec<String> = lines.iter().map(TimesheetLine::render).collect();
+
+    assert_eq!(
+      rendered,
+      vec![
+        "2024-05-02 1:30 acme/website/dev".to_string(),
+        r#"2024-05-02 1:30 "ACME GmbH/Web\/App/Dev ops" "Say \"hi\" # not a comment""#.to_string(),
+      ]
+    );
+    assert_eq!(parse(&rendered.join("\n"), &BTreeMap::new())?, lines);
+
+    Ok(())
+  }
+}
@@ -1,5 +1,6 @@
 use super::OutputFormat;
-use super::{find_customer, find_project, find_service};
+use super::{check_note_support, check_tracker_support, resolve_target, split_target};
+use crate::config::Config;
 use acari_lib::{AcariError, Client, Day, Minutes, TimeEntry, Tracker};
 use clap::Args;
 use prettytable::{format, row, table};
@@ -7,44 +8,47 @@ use serde_json::json;
 
 #[derive(Debug, Args, PartialEq, Eq)]
 pub struct StartCmd {
-  #[clap(help = "Customer name")]
-  customer: String,
-  #[clap(help = "Project name")]
-  project: String,
-  #[clap(help = "Service name")]
-  service: String,
-  #[clap(help = "Optional: Starting offset (minutes or hh:mm)")]
-  offset: Option<Minutes>,
+  #[clap(
+    value_name = "ARGS",
+    num_args = 0..=4,
+    help = "[@<alias> | <customer> <project> <service>] [<starting offset (minutes or hh:mm)>] (customer, project and service may be omitted if set in a .acari.toml)"
+  )]
+  args: Vec<String>,
   #[clap(short, long, help = "Optional note")]
   note: Option<String>,
+  #[clap(long, help = "Include archived customers, projects and services")]
+  archived: bool,
 }
 
 impl StartCmd {
-  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
-    let customer = find_customer(client, &self.customer)?;
-    let project = find_project(client, &customer.id, &self.project)?;
-    let service = find_service(client, &project.id, &self.service)?;
+  pub fn run(&self, client: &dyn Client, config: &Config, output_format: OutputFormat) -> Result<(), AcariError> {
+    check_tracker_support(client)?;
+    let (target, remaining) = split_target(config, &self.args, 0, 1)?;
+    let note = self.note.clone().or_else(|| target.note.clone());
+    check_note_support(client, &note)?;
+    let offset: Option<Minutes> = remaining.first().map(|o| o.parse()).transpose()?;
+    let (customer, project, service) = resolve_target(client, &target, self.archived)?;
     let date = Day::Today.as_date();
 
-    let maybe_existing = match self.offset {
-      Some(_) => None,
-      None => {
-        let mut existing: Vec<TimeEntry> = client
-          .get_time_entries(date.into())?
-          .into_iter()
-          .filter(|e| e.date_at == date && e.customer_id == customer.id && e.project_id == project.id && e.service_id == service.id)
-          .collect();
-
-        existing.sort_by(|e1, e2| e2.created_at.cmp(&e1.created_at));
-
-        existing.into_iter().next()
-      }
+    // Today's entries are needed to continue an existing entry, and they also resolve the tracker
+    let mut known_entries = match offset {
+      Some(_) => vec![],
+      None => client.get_time_entries(date.into())?,
     };
+    let maybe_existing = known_entries
+      .iter()
+      .filter(|e| e.date_at == date && e.customer_id == customer.id && e.project_id == project.id && e.service_id == service.id)
+      .max_by_key(|e| e.created_at)
+      .cloned();
     let entry = match maybe_existing {
       Some(existing) => existing,
-      None => client.create_time_entry(date.into(), &project.id, &service.id, self.offset.unwrap_or_default(), self.note.clone())?,
+      None => {
+        let created = client.create_time_entry(date.into(), &project.id, &service.id, offset.unwrap_or_default(), note)?;
+        known_entries.push(created.clone());
+        created
+      }
     };
-    let tracker = client.create_tracker(&entry.id)?;
+    let tracker = client.create_tracker_with_entries(&entry.id, &known_entries)?;
 
     match output_format {
       OutputFormat::Pretty => print_pretty(Some(&entry), &tracker),
@@ -57,7 +61,10 @@ impl StartCmd {
 }
 
 pub fn tracking(client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
-  let tracker = client.get_tracker()?;
+  check_tracker_support(client)?;
+
+  // The tracked entry is usually one of today's, which saves fetching it separately
+  let (_, tracker) = client.get_time_entries_with_tracker(Day::Today.into())?;
   let maybe_entry = if let Some(tracking_entry) = &tracker.tracking_time_entry {
     Some(tracking_entry)
   } else if let Some(tracking_entry) = &tracker.stopped_time_entry {
@@ -76,9 +83,11 @@ pub fn tracking(client: &dyn Client, output_format: OutputFormat) -> Result<(),
 }
 
 pub fn stop(client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
-  let current_tracker = client.get_tracker()?;
+  check_tracker_support(client)?;
+
+  let (known_entries, current_tracker) = client.get_time_entries_with_tracker(Day::Today.into())?;
   let (update_tracker, maybe_entry) = if let Some(tracking_entry) = &current_tracker.tracking_time_entry {
-    (client.delete_tracker(&tracking_entry.id)?, Some(tracking_entry))
+    (client.delete_tracker_with_entries(&tracking_entry.id, &known_entries)?, Some(tracking_entry))
   } else if let Some(tracking_entry) = &current_tracker.stopped_time_entry {
     (current_tracker.clone(), Some(tracking_entry))
   } else {
@@ -0,0 +1,540 @@
+use crate::commands::entries::current_minutes;
+use crate::commands::{alias_target, check_note_support, check_tracker_support, resolve_target_with, EntryTarget};
+use crate::config::{Alias, Config, LocalConfig};
+use acari_lib::{user_error, AcariError, Client, DateSpan, Day, Minutes, TimeEntry, TimeEntryId, Tracker};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+pub const FIELD_LABELS: [&str; 6] = ["Date", "Customer", "Project", "Service", "Time", "Note"];
+const DATE: usize = 0;
+const CUSTOMER: usize = 1;
+const PROJECT: usize = 2;
+const SERVICE: usize = 3;
+const TIME: usize = 4;
+const NOTE: usize = 5;
+
+/// Input form to add a new or edit an existing time entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryForm {
+  /// Entry being edited, `None` if a new one is added
+  pub entry_id: Option<TimeEntryId>,
+  pub values: [String; 6],
+  pub focus: usize,
+}
+
+impl EntryForm {
+  /// Only time and note of an existing entry can be changed.
+  pub fn is_editable(&self, field: usize) -> bool {
+    self.entry_id.is_none() || field == TIME || field == NOTE
+  }
+
+  fn move_focus(&mut self, forward: bool) {
+    let count = FIELD_LABELS.len();
+    loop {
+      self.focus = if forward {
+        (self.focus + 1) % count
+      } else {
+        (self.focus + count - 1) % count
+      };
+      if self.is_editable(self.focus) {
+        break;
+      }
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+  Browse,
+  Form(EntryForm),
+  ConfirmDelete(TimeEntryId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+  Info(String),
+  Error(String),
+}
+
+/// State of the tui, independent of the terminal so that it can be driven by key events in tests.
+pub struct App<'a> {
+  client: &'a dyn Client,
+  defaults: LocalConfig,
+  aliases: BTreeMap<String, Alias>,
+  pub week_start: NaiveDate,
+  /// Entries of the week sorted by day
+  pub time_entries: Vec<TimeEntry>,
+  pub tracker: Tracker,
+  loaded_at: Instant,
+  pub selected: usize,
+  pub mode: Mode,
+  pub status: Option<Status>,
+  pub quit: bool,
+}
+
+impl<'a> App<'a> {
+  pub fn new(client: &'a dyn Client, config: &Config, today: NaiveDate) -> App<'a> {
+    let mut app = App {
+      client,
+      defaults: config.local.clone(),
+      aliases: config.aliases.clone(),
+      week_start: week_start(today),
+      time_entries: vec![],
+      tracker: Tracker {
+        since: None,
+        tracking_time_entry: None,
+        stopped_time_entry: None,
+      },
+      loaded_at: Instant::now(),
+      selected: 0,
+      mode: Mode::Browse,
+      status: None,
+      quit: false,
+    };
+    app.reload();
+    app
+  }
+
+  pub fn week_days(&self) -> Vec<NaiveDate> {
+    (0..7).map(|offset| self.week_start + Duration::days(offset)).collect()
+  }
+
+  pub fn selected_entry(&self) -> Option<&TimeEntry> {
+    self.time_entries.get(self.selected)
+  }
+
+  pub fn is_tracking(&self, entry: &TimeEntry) -> bool {
+    self.tracker.tracking_time_entry.as_ref().filter(|t| t.id == entry.id).is_some()
+  }
+
+  /// Minutes of an entry, the tracked one keeps counting since the last reload.
+  pub fn minutes(&self, entry: &TimeEntry) -> Minutes {
+    let minutes = current_minutes(entry, &self.tracker.tracking_time_entry);
+    if self.is_tracking(entry) {
+      Minutes(minutes.0 + (self.loaded_at.elapsed().as_secs() / 60) as u32)
+    } else {
+      minutes
+    }
+  }
+
+  /// Time of the currently tracked entry as `h:mm:ss`.
+  pub fn tracker_clock(&self) -> Option<String> {
+    let tracking = self.tracker.tracking_time_entry.as_ref()?;
+    let seconds = u64::from(tracking.minutes.0) * 60 + self.loaded_at.elapsed().as_secs();
+
+    Some(format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60))
+  }
+
+  pub fn reload(&mut self) {
+    if let Err(err) = self.load() {
+      self.status = Some(Status::Error(err.to_string()));
+    }
+  }
+
+  fn load(&mut self) -> Result<(), AcariError> {
+    let week_end = self.week_start + Duration::days(6);
+    let (mut time_entries, tracker) = self.client.get_time_entries_with_tracker(DateSpan::FromTo(self.week_start, week_end))?;
+
+    time_entries.sort_by(|t1, t2| t1.date_at.cmp(&t2.date_at).then_with(|| t1.created_at.cmp(&t2.created_at)));
+    self.time_entries = time_entries;
+    self.tracker = tracker;
+    self.loaded_at = Instant::now();
+    self.selected = self.selected.min(self.time_entries.len().saturating_sub(1));
+
+    Ok(())
+  }
+
+  pub fn handle_key(&mut self, key: KeyEvent) {
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+      self.quit = true;
+      return;
+    }
+    let result = match &self.mode {
+      Mode::Browse => self.handle_browse_key(key),
+      Mode::Form(_) => self.handle_form_key(key),
+      Mode::ConfirmDelete(entry_id) => {
+        let entry_id = entry_id.clone();
+        self.mode = Mode::Browse;
+        self.status = None;
+        self.handle_confirm_delete_key(key, &entry_id)
+      }
+    };
+    if let Err(err) = result {
+      self.status = Some(Status::Error(err.to_string()));
+    }
+  }
+
+  fn handle_browse_key(&mut self, key: KeyEvent) -> Result<(), AcariError> {
+    self.status = None;
+    match key.code {
+      KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+      KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+      KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(self.time_entries.len().saturating_sub(1)),
+      KeyCode::Left | KeyCode::Char('h') => self.change_week(self.week_start - Duration::days(7)),
+      KeyCode::Right | KeyCode::Char('l') => self.change_week(self.week_start + Duration::days(7)),
+      KeyCode::Char('t') => self.change_week(week_start(Local::now().naive_local().date())),
+      KeyCode::Char('r') => self.reload(),
+      KeyCode::Char('a') => self.mode = Mode::Form(self.new_entry_form()),
+      KeyCode::Char('e') | KeyCode::Enter => {
+        let entry = self.unlocked_selection()?;
+        self.mode = Mode::Form(EntryForm {
+          entry_id: Some(entry.id.clone()),
+          values: [
+            entry.date_at.to_string(),
+            entry.customer_name.clone(),
+            entry.project_name.clone(),
+            entry.service_name.clone(),
+            entry.minutes.to_string(),
+            entry.note.clone(),
+          ],
+          focus: TIME,
+        });
+      }
+      KeyCode::Char('d') | KeyCode::Delete => {
+        let entry_id = self.unlocked_selection()?.id.clone();
+        self.mode = Mode::ConfirmDelete(entry_id);
+      }
+      KeyCode::Char('s') => {
+        check_tracker_support(self.client)?;
+        let entry_id = self.selected_entry().ok_or_else(|| user_error!("No entry selected"))?.id.clone();
+        self.client.create_tracker_with_entries(&entry_id, &self.time_entries)?;
+        self.done("Tracking started");
+      }
+      KeyCode::Char('x') => {
+        check_tracker_support(self.client)?;
+        let entry_id = match &self.tracker.tracking_time_entry {
+          Some(tracking) => tracking.id.clone(),
+          None => return Err(user_error!("Currently not tracking anything")),
+        };
+        self.client.delete_tracker_with_entries(&entry_id, &self.time_entries)?;
+        self.done("Tracking stopped");
+      }
+      _ => (),
+    }
+    Ok(())
+  }
+
+  fn handle_form_key(&mut self, key: KeyEvent) -> Result<(), AcariError> {
+    let form = match &mut self.mode {
+      Mode::Form(form) => form,
+      _ => return Ok(()),
+    };
+    match key.code {
+      KeyCode::Esc => self.mode = Mode::Browse,
+      KeyCode::Tab | KeyCode::Down => form.move_focus(true),
+      KeyCode::BackTab | KeyCode::Up => form.move_focus(false),
+      KeyCode::Backspace => {
+        form.values[form.focus].pop();
+      }
+      KeyCode::Char(c) => form.values[form.focus].push(c),
+      KeyCode::Enter => {
+        let form = form.clone();
+        self.submit(&form)?;
+        self.mode = Mode::Browse;
+      }
+      _ => (),
+    }
+    Ok(())
+  }
+
+  fn handle_confirm_delete_key(&mut self, key: KeyEvent, entry_id: &TimeEntryId) -> Result<(), AcariError> {
+    if key.code == KeyCode::Char('y') {
+      self.client.delete_time_entry(entry_id)?;
+      self.done("Entry deleted");
+    }
+    Ok(())
+  }
+
+  fn submit(&mut self, form: &EntryForm) -> Result<(), AcariError> {
+    let minutes: Minutes = form.values[TIME].trim().parse()?;
+    let note = Some(form.values[NOTE].trim().to_string()).filter(|note| !note.is_empty());
+
+    match &form.entry_id {
+      Some(entry_id) => {
+        // Clearing the note of an existing entry requires an empty one
+        let note = note.or_else(|| {
+          self
+            .time_entries
+            .iter()
+            .find(|e| e.id == *entry_id)
+            .filter(|e| !e.note.is_empty())
+            .map(|_| String::new())
+        });
+        check_note_support(self.client, &note)?;
+        self.client.update_time_entry(entry_id, minutes, note)?;
+        self.done("Entry updated");
+      }
+      None => {
+        let day: Day = form.values[DATE].trim().parse()?;
+        let name = |field: usize| Some(form.values[field].trim().to_string()).filter(|name| !name.is_empty());
+        let target = match form.values[CUSTOMER].trim().strip_prefix('@') {
+          Some(alias_name) => alias_target(&self.aliases, alias_name)?,
+          None => EntryTarget {
+            customer: name(CUSTOMER),
+            project: name(PROJECT),
+            service: name(SERVICE),
+            note: None,
+          },
+        };
+        let note = note.or(target.note.clone());
+        check_note_support(self.client, &note)?;
+        // The terminal belongs to the tui, so ambiguous names cannot be picked
+        let (_, project, service) = resolve_target_with(self.client, &target, false, false)?;
+
+        self.client.create_time_entry(day, &project.id, &service.id, minutes, note)?;
+        self.done("Entry added");
+      }
+    }
+    Ok(())
+  }
+
+  /// New entries are added to the day of the selected entry, customer, project and service are
+  /// taken from the local defaults or the selected entry.
+  fn new_entry_form(&self) -> EntryForm {
+    let today = Local::now().naive_local().date();
+    let selected = self.selected_entry();
+    let date = match selected {
+      Some(entry) => entry.date_at,
+      None if self.week_days().contains(&today) => today,
+      None => self.week_start,
+    };
+    let name =
+      |default: &Option<String>, of_entry: fn(&TimeEntry) -> &String| default.clone().or_else(|| selected.map(|e| of_entry(e).clone())).unwrap_or_default();
+
+    let values = [
+      date.to_string(),
+      name(&self.defaults.customer, |e| &e.customer_name),
+      name(&self.defaults.project, |e| &e.project_name),
+      name(&self.defaults.service, |e| &e.service_name),
+      String::new(),
+      String::new(),
+    ];
+    let focus = if values[CUSTOMER].is_empty() { CUSTOMER } else { TIME };
+
+    EntryForm { entry_id: None, values, focus }
+  }
+
+  fn unlocked_selection(&self) -> Result<&TimeEntry, AcariError> {
+    match self.selected_entry() {
+      Some(entry) if entry.locked => Err(user_error!("Entry is locked")),
+      Some(entry) => Ok(entry),
+      None => Err(user_error!("No entry selected")),
+    }
+  }
+
+  fn change_week(&mut self, week_start: NaiveDate) {
+    self.week_start = week_start;
+    self.selected = 0;
+    self.reload();
+  }
+
+  fn done(&mut self, message: &str) {
+    self.reload();
+    if self.status.is_none() {
+      self.status = Some(Status::Info(message.to_string()));
+    }
+  }
+}
+
+fn week_start(day: NaiveDate) -> NaiveDate {
+  day - Duration::days(i64::from(day.weekday().num_days_from_monday()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::commands::test_support::{self, date};
+  use acari_lib::{MockClient, Mutation, ProjectId, ServiceId};
+
+  fn entry(id: u64, day: u32, minutes: u32, locked: bool) -> TimeEntry {
+    TimeEntry {
+      locked,
+      ..test_support::entry(id, day, minutes)
+    }
+  }
+
+  fn mock_client() -> MockClient {
+    test_support::mock_client()
+      .with_time_entry(entry(1, 6, 60, false))
+      .with_time_entry(entry(2, 7, 30, true))
+      .with_time_entry(entry(3, 13, 45, false))
+  }
+
+  fn press(app: &mut App, codes: &[KeyCode]) {
+    for code in codes {
+      app.handle_key(KeyEvent::from(*code));
+    }
+  }
+
+  fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+      app.handle_key(KeyEvent::from(KeyCode::Char(c)));
+    }
+  }
+
+  #[test]
+  fn test_browse_weeks() {
+    let client = mock_client();
+    let mut app = App::new(&client, &Config::default(), date(8));
+
+    assert_eq!(app.week_start, date(6));
+    assert_eq!(app.time_entries.len(), 2);
+
+    press(&mut app, &[KeyCode::Down, KeyCode::Down]);
+    assert_eq!(app.selected, 1);
+
+    press(&mut app, &[KeyCode::Right]);
+    assert_eq!(app.week_start, date(13));
+    assert_eq!(app.selected, 0);
+    assert_eq!(app.time_entries.iter().map(|e| e.id.clone()).collect::<Vec<_>>(), vec![TimeEntryId::Num(3)]);
+
+    press(&mut app, &[KeyCode::Char('q')]);
+    assert!(app.quit);
+  }
+
+  #[test]
+  fn test_add_entry() {
+    let client = mock_client();
+    let mut app = App::new(&client, &Config::default(), date(8));
+
+    press(&mut app, &[KeyCode::Char('a')]);
+    type_text(&mut app, "1:30");
+    press(&mut app, &[KeyCode::Tab]);
+    type_text(&mut app, "Fix login");
+    press(&mut app, &[KeyCode::Enter]);
+
+    assert_eq!(app.mode, Mode::Browse);
+    assert_eq!(app.status, Some(Status::Info("Entry added".to_string())));
+    assert_eq!(app.time_entries.len(), 3);
+    assert_eq!(
+      client.mutations(),
+      vec![Mutation::CreateTimeEntry {
+        date: date(6),
+        project_id: ProjectId::Num(10),
+        service_id: ServiceId::Num(100),
+        minutes: Minutes(90),
+        note: Some("Fix login".to_string()),
+      }]
+    );
+  }
+
+  #[test]
+  fn test_add_entry_by_alias() {
+    let client = mock_client();
+    let mut config = Config::default();
+    config.aliases.insert(
+      "web".to_string(),
+      Alias {
+        customer: "acme".to_string(),
+        project: "web".to_string(),
+        service: "dev".to_string(),
+        note: Some("Relaunch".to_string()),
+      },
+    );
+    let mut app = App::new(&client, &config, date(8));
+
+    press(&mut app, &[KeyCode::Char('a')]);
+    type_text(&mut app, "15");
+    press(
+      &mut app,
+      &[
+        KeyCode::Up,
+        KeyCode::Up,
+        KeyCode::Up,
+        KeyCode::Backspace,
+        KeyCode::Backspace,
+        KeyCode::Backspace,
+        KeyCode::Backspace,
+      ],
+    );
+    type_text(&mut app, "@web");
+    press(&mut app, &[KeyCode::Enter]);
+
+    assert_eq!(app.status, Some(Status::Info("Entry added".to_string())));
+    assert_eq!(
+      client.mutations(),
+      vec![Mutation::CreateTimeEntry {
+        date: date(6),
+        project_id: ProjectId::Num(10),
+        service_id: ServiceId::Num(100),
+        minutes: Minutes(15),
+        note: Some("Relaunch".to_string()),
+      }]
+    );
+  }
+
+  #[test]
+  fn test_invalid_form_stays_open() {
+    let client = mock_client();
+    let mut app = App::new(&client, &Config::default(), date(8));
+
+    press(&mut app, &[KeyCode::Char('a')]);
+    type_text(&mut app, "later");
+    press(&mut app, &[KeyCode::Enter]);
+
+    assert!(matches!(app.mode, Mode::Form(_)));
+    assert!(matches!(app.status, Some(Status::Error(_))));
+    assert_eq!(client.mutations(), vec![]);
+  }
+
+  #[test]
+  fn test_edit_and_delete_entry() {
+    let client = mock_client();
+    let mut app = App::new(&client, &Config::default(), date(8));
+
+    press(&mut app, &[KeyCode::Char('e'), KeyCode::Backspace, KeyCode::Backspace]);
+    type_text(&mut app, "15");
+    press(&mut app, &[KeyCode::Enter]);
+    press(&mut app, &[KeyCode::Char('d'), KeyCode::Char('n')]);
+    press(&mut app, &[KeyCode::Char('d'), KeyCode::Char('y')]);
+
+    assert_eq!(
+      client.mutations(),
+      vec![
+        Mutation::UpdateTimeEntry {
+          entry_id: TimeEntryId::Num(1),
+          minutes: Minutes(75),
+          note: None,
+        },
+        Mutation::DeleteTimeEntry { entry_id: TimeEntryId::Num(1) },
+      ]
+    );
+    assert_eq!(app.time_entries.len(), 1);
+  }
+
+  #[test]
+  fn test_locked_entries_are_read_only() {
+    let client = mock_client();
+    let mut app = App::new(&client, &Config::default(), date(8));
+
+    press(&mut app, &[KeyCode::Down, KeyCode::Char('e')]);
+    assert_eq!(app.mode, Mode::Browse);
+    assert_eq!(app.status, Some(Status::Error("User error: Entry is locked".to_string())));
+
+    press(&mut app, &[KeyCode::Char('d')]);
+    assert_eq!(app.mode, Mode::Browse);
+    assert_eq!(client.mutations(), vec![]);
+  }
+
+  #[test]
+  fn test_start_and_stop_tracker() {
+    let client = mock_client();
+    let mut app = App::new(&client, &Config::default(), date(8));
+
+    press(&mut app, &[KeyCode::Char('s')]);
+    assert!(app.is_tracking(&app.time_entries[0]));
+    assert_eq!(app.tracker_clock(), Some("1:00:00".to_string()));
+
+    press(&mut app, &[KeyCode::Char('x')]);
+    assert_eq!(app.tracker_clock(), None);
+    assert_eq!(
+      client.mutations(),
+      vec![
+        Mutation::CreateTracker { entry_id: TimeEntryId::Num(1) },
+        Mutation::DeleteTracker { entry_id: TimeEntryId::Num(1) },
+      ]
+    );
+  }
+}
@@ -0,0 +1,37 @@
+use crate::config::Config;
+use acari_lib::{AcariError, Client};
+use chrono::Local;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use std::time::Duration;
+
+mod app;
+mod ui;
+
+use app::App;
+
+/// Redraw at least once a second to keep the tracker running.
+const TICK: Duration = Duration::from_secs(1);
+
+pub fn tui(client: &dyn Client, config: &Config) -> Result<(), AcariError> {
+  let mut app = App::new(client, config, Local::now().naive_local().date());
+  let mut terminal = ratatui::init();
+  let result = run(&mut terminal, &mut app);
+
+  ratatui::restore();
+  result
+}
+
+fn run(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> Result<(), AcariError> {
+  while !app.quit {
+    terminal.draw(|frame| ui::render(frame, app))?;
+
+    if event::poll(TICK)? {
+      if let Event::Key(key) = event::read()? {
+        if key.kind == KeyEventKind::Press {
+          app.handle_key(key);
+        }
+      }
+    }
+  }
+  Ok(())
+}
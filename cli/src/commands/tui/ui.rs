@@ -0,0 +1,132 @@
+use super::app::{App, EntryForm, Mode, Status, FIELD_LABELS};
+use crate::commands::entries::group_by_day;
+use acari_lib::Minutes;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+
+const HELP: &str = "←/→ week  ↑/↓ select  a add  e edit  d delete  s start  x stop  t today  r reload  q quit";
+
+pub fn render(frame: &mut Frame, app: &App) {
+  let [header_area, entries_area, status_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+
+  render_header(frame, header_area, app);
+  render_entries(frame, entries_area, app);
+  render_status(frame, status_area, app);
+
+  if let Mode::Form(form) = &app.mode {
+    render_form(frame, entries_area, form);
+  }
+}
+
+fn render_header(frame: &mut Frame, area: Rect, app: &App) {
+  let week = format!(
+    "Week {} ({} - {})",
+    app.week_start.format("%V/%G"),
+    app.week_start,
+    app.week_start + chrono::Duration::days(6)
+  );
+  let mut spans = vec![Span::styled(week, Style::default().add_modifier(Modifier::BOLD))];
+
+  if let (Some(tracking), Some(clock)) = (&app.tracker.tracking_time_entry, app.tracker_clock()) {
+    spans.push(Span::raw("  Tracking "));
+    spans.push(Span::styled(
+      format!("{} {} / {} / {}", clock, tracking.customer_name, tracking.project_name, tracking.service_name),
+      Style::default().fg(Color::Yellow),
+    ));
+  }
+  frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn render_entries(frame: &mut Frame, area: Rect, app: &App) {
+  let grouped = group_by_day(&app.time_entries);
+  let mut rows = vec![];
+  let mut selected_row = None;
+  let mut entry_index = 0;
+  let mut total = Minutes::default();
+
+  for day in app.week_days() {
+    let group = grouped.iter().find(|(d, _)| **d == day).map(|(_, group)| group.as_slice()).unwrap_or_default();
+    let sum = group.iter().map(|e| app.minutes(e)).sum::<Minutes>();
+    total += sum;
+    rows.push(Row::new(vec![day.format("%a %Y-%m-%d").to_string(), sum.to_string()]).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+
+    for entry in group {
+      if entry_index == app.selected {
+        selected_row = Some(rows.len());
+      }
+      let style = if app.is_tracking(entry) {
+        Style::default().fg(Color::Yellow)
+      } else if entry.locked {
+        Style::default().fg(Color::Red)
+      } else {
+        Style::default()
+      };
+      rows.push(
+        Row::new(vec![
+          Cell::from(""),
+          Cell::from(app.minutes(entry).to_string()),
+          Cell::from(entry.customer_name.as_str()),
+          Cell::from(entry.project_name.as_str()),
+          Cell::from(entry.service_name.as_str()),
+          Cell::from(entry.note.as_str()),
+        ])
+        .style(style),
+      );
+      entry_index += 1;
+    }
+  }
+
+  let widths = [
+    Constraint::Length(14),
+    Constraint::Length(6),
+    Constraint::Percentage(20),
+    Constraint::Percentage(20),
+    Constraint::Percentage(20),
+    Constraint::Fill(1),
+  ];
+  let table = Table::new(rows, widths)
+    .header(Row::new(vec!["Day", "Time", "Customer", "Project", "Service", "Note"]).style(Style::default().add_modifier(Modifier::UNDERLINED)))
+    .block(Block::default().borders(Borders::ALL).title(format!(" Total {} ", total)))
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+  let mut state = TableState::default().with_selected(selected_row);
+
+  frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_status(frame: &mut Frame, area: Rect, app: &App) {
+  let line = match (&app.mode, &app.status) {
+    (_, Some(Status::Error(message))) => Line::styled(message.as_str(), Style::default().fg(Color::Red)),
+    (Mode::ConfirmDelete(_), _) => Line::styled("Delete selected entry? (y/N)", Style::default().fg(Color::Yellow)),
+    (Mode::Form(_), _) => Line::raw("Tab/↑/↓ next field  Enter save  Esc cancel"),
+    (_, Some(Status::Info(message))) => Line::styled(message.as_str(), Style::default().fg(Color::Green)),
+    (_, None) => Line::raw(HELP),
+  };
+  frame.render_widget(Paragraph::new(line), area);
+}
+
+fn render_form(frame: &mut Frame, area: Rect, form: &EntryForm) {
+  let width = area.width.min(60);
+  let height = (FIELD_LABELS.len() as u16 + 2).min(area.height);
+  let area = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+  let title = if form.entry_id.is_some() { " Edit entry " } else { " Add entry " };
+  let lines: Vec<Line> = FIELD_LABELS
+    .iter()
+    .enumerate()
+    .map(|(field, label)| {
+      let style = if field == form.focus {
+        Style::default().add_modifier(Modifier::REVERSED)
+      } else if form.is_editable(field) {
+        Style::default()
+      } else {
+        Style::default().fg(Color::DarkGray)
+      };
+      Line::from(vec![Span::raw(format!("{:>9}: ", label)), Span::styled(form.values[field].as_str(), style)])
+    })
+    .collect();
+
+  frame.render_widget(Clear, area);
+  frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), area);
+}
@@ -0,0 +1,13 @@
+use acari_lib::{AcariError, Client, ProjectId};
+
+pub fn warm_cache(client: &dyn Client) -> Result<(), AcariError> {
+  client.get_account()?;
+  client.get_myself()?;
+  client.get_customers()?;
+
+  let project_ids: Vec<ProjectId> = client.get_projects()?.into_iter().filter(|p| !p.archived).map(|p| p.id).collect();
+
+  client.get_services_for_projects(&project_ids)?;
+
+  Ok(())
+}
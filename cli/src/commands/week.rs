@@ -0,0 +1,252 @@
+use super::{current_minutes, OutputFormat};
+use acari_lib::{AcariError, Client, DateSpan, Minutes, TimeEntry};
+use chrono::NaiveDate;
+use clap::Args;
+use prettytable::{format, Attr, Cell, Row, Table};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Args, PartialEq, Eq)]
+pub struct WeekCmd {
+  #[clap(
+    default_value = "this-week",
+    help = "Date span to show\n(today, yesterday, this-week, last-week,\n this-month, last-month, yyyy-mm-dd, yyyy-mm-dd/yyyy-mm-dd)"
+  )]
+  span: DateSpan,
+  #[clap(long, help = "Print as CSV (instead of the output format)")]
+  csv: bool,
+}
+
+impl WeekCmd {
+  pub fn run(&self, client: &dyn Client, output_format: OutputFormat) -> Result<(), AcariError> {
+    let (time_entries, tracker) = client.get_time_entries_with_tracker(self.span)?;
+    let grid = Grid::new(&time_entries, &tracker.tracking_time_entry, self.span.date_range());
+
+    match output_format {
+      _ if self.csv => print_csv(&grid),
+      OutputFormat::Pretty => print_pretty(&grid),
+      OutputFormat::Json => print_json(&grid)?,
+      OutputFormat::Flat => print_flat(&grid),
+    }
+
+    Ok(())
+  }
+}
+
+/// Time per customer/project/service (rows) and day (columns).
+#[derive(Debug, PartialEq, Eq)]
+struct Grid {
+  days: Vec<NaiveDate>,
+  rows: Vec<GridRow>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct GridRow {
+  customer: String,
+  project: String,
+  service: String,
+  minutes: Vec<Minutes>,
+  tracking: bool,
+}
+
+impl Grid {
+  fn new(time_entries: &[TimeEntry], tracking_time_entry: &Option<TimeEntry>, (from, to): (NaiveDate, NaiveDate)) -> Grid {
+    let days: Vec<NaiveDate> = from.iter_days().take_while(|day| *day <= to).collect();
+    let mut rows: BTreeMap<(&str, &str, &str), GridRow> = BTreeMap::new();
+
+    for entry in time_entries {
+      let column = match days.iter().position(|day| *day == entry.date_at) {
+        Some(column) => column,
+        None => continue,
+      };
+      let row = rows
+        .entry((entry.customer_name.as_str(), entry.project_name.as_str(), entry.service_name.as_str()))
+        .or_insert_with(|| GridRow {
+          customer: entry.customer_name.clone(),
+          project: entry.project_name.clone(),
+          service: entry.service_name.clone(),
+          minutes: vec![Minutes::default(); days.len()],
+          tracking: false,
+        });
+      row.minutes[column] += current_minutes(entry, tracking_time_entry);
+      row.tracking |= tracking_time_entry.as_ref().map(|t| t.id == entry.id).unwrap_or(false);
+    }
+
+    Grid {
+      days,
+      rows: rows.into_values().collect(),
+    }
+  }
+
+  fn day_totals(&self) -> Vec<Minutes> {
+    (0..self.days.len())
+      .map(|column| self.rows.iter().map(|row| row.minutes[column]).sum())
+      .collect()
+  }
+
+  fn total(&self) -> Minutes {
+    self.rows.iter().map(GridRow::total).sum()
+  }
+}
+
+impl GridRow {
+  fn total(&self) -> Minutes {
+    self.minutes.iter().copied().sum()
+  }
+}
+
+fn time_cell(minutes: Minutes) -> Cell {
+  if minutes == Minutes::default() {
+    Cell::new("")
+  } else {
+    Cell::new(&minutes.to_string())
+  }
+}
+
+fn print_pretty(grid: &Grid) {
+  if grid.rows.is_empty() {
+    println!("No entries found");
+    return;
+  }
+
+  let mut week_table = Table::new();
+  let mut titles = vec![Cell::new("Customer"), Cell::new("Project"), Cell::new("Service")];
+  titles.extend(grid.days.iter().map(|day| Cell::new(&day.format("%a %m-%d").to_string())));
+  titles.push(Cell::new("Total"));
+  week_table.set_titles(Row::new(titles));
+  week_table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+  for row in &grid.rows {
+    let mut cells = vec![Cell::new(&row.customer), Cell::new(&row.project), Cell::new(&row.service)];
+    cells.extend(row.minutes.iter().map(|minutes| time_cell(*minutes)));
+    cells.push(time_cell(row.total()).with_style(Attr::Bold));
+    if row.tracking {
+      cells = cells.into_iter().map(|cell| cell.style_spec("Fy")).collect();
+    }
+    week_table.add_row(Row::new(cells));
+  }
+
+  let mut totals = vec![Cell::new("Total").with_style(Attr::Bold), Cell::new(""), Cell::new("")];
+  totals.extend(grid.day_totals().into_iter().map(|minutes| time_cell(minutes).with_style(Attr::Bold)));
+  totals.push(time_cell(grid.total()).with_style(Attr::Bold));
+  week_table.add_row(Row::new(totals));
+
+  week_table.printstd();
+}
+
+fn print_json(grid: &Grid) -> Result<(), AcariError> {
+  let rows: Vec<_> = grid
+    .rows
+    .iter()
+    .map(|row| {
+      json!({
+        "customer": row.customer,
+        "project": row.project,
+        "service": row.service,
+        "minutes": grid.days.iter().zip(&row.minutes).map(|(day, minutes)| (day.to_string(), *minutes)).collect::<BTreeMap<_, _>>(),
+        "total": row.total(),
+        "tracking": row.tracking,
+      })
+    })
+    .collect();
+  let day_totals: BTreeMap<_, _> = grid.days.iter().map(ToString::to_string).zip(grid.day_totals()).collect();
+
+  println!(
+    "{}",
+    serde_json::to_string_pretty(&json!({
+      "days": grid.days,
+      "rows": rows,
+      "day_totals": day_totals,
+      "total": grid.total(),
+    }))?
+  );
+
+  Ok(())
+}
+
+fn print_flat(grid: &Grid) {
+  for row in &grid.rows {
+    let minutes: Vec<String> = row.minutes.iter().map(ToString::to_string).collect();
+    println!("{}\t{}\t{}\t{}\t{}", row.customer, row.project, row.service, minutes.join("\t"), row.total());
+  }
+}
+
+fn print_csv(grid: &Grid) {
+  let mut header = vec!["Customer".to_string(), "Project".to_string(), "Service".to_string()];
+  header.extend(grid.days.iter().map(ToString::to_string));
+  header.push("Total".to_string());
+  println!("{}", header.join(","));
+
+  for row in &grid.rows {
+    let mut fields = vec![csv_field(&row.customer), csv_field(&row.project), csv_field(&row.service)];
+    fields.extend(row.minutes.iter().map(ToString::to_string));
+    fields.push(row.total().to_string());
+    println!("{}", fields.join(","));
+  }
+
+  let mut totals = vec!["Total".to_string(), "".to_string(), "".to_string()];
+  totals.extend(grid.day_totals().iter().map(ToString::to_string));
+  totals.push(grid.total().to_string());
+  println!("{}", totals.join(","));
+}
+
+fn csv_field(value: &str) -> String {
+  if value.contains([',', '"', '\n']) {
+    format!("\"{}\"", value.replace('"', "\"\""))
+  } else {
+    value.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::commands::test_support::{self, date};
+
+  fn entry(id: u64, day: u32, minutes: u32, service: &str) -> TimeEntry {
+    TimeEntry {
+      service_name: service.to_string(),
+      ..test_support::entry(id, day, minutes)
+    }
+  }
+
+  fn row(service: &str, minutes: Vec<u32>, tracking: bool) -> GridRow {
+    GridRow {
+      customer: "ACME".to_string(),
+      project: "Website".to_string(),
+      service: service.to_string(),
+      minutes: minutes.into_iter().map(Minutes).collect(),
+      tracking,
+    }
+  }
+
+  #[test]
+  fn test_grid() {
+    let time_entries = vec![
+      entry(1, 6, 60, "Development"),
+      entry(2, 6, 30, "Development"),
+      entry(3, 7, 15, "Meeting"),
+      entry(4, 8, 45, "Development"),
+      entry(5, 20, 45, "Development"),
+    ];
+    let tracking = Some(entry(4, 8, 50, "Development"));
+    let grid = Grid::new(&time_entries, &tracking, (date(6), date(8)));
+
+    assert_eq!(
+      grid,
+      Grid {
+        days: vec![date(6), date(7), date(8)],
+        rows: vec![row("Development", vec![90, 0, 50], true), row("Meeting", vec![0, 15, 0], false)],
+      }
+    );
+    assert_eq!(grid.day_totals(), vec![Minutes(90), Minutes(15), Minutes(50)]);
+    assert_eq!(grid.total(), Minutes(155));
+  }
+
+  #[test]
+  fn test_csv_field() {
+    assert_eq!(csv_field("ACME"), "ACME");
+    assert_eq!(csv_field("ACME, Inc."), "\"ACME, Inc.\"");
+    assert_eq!(csv_field("The \"Web\""), "\"The \"\"Web\"\"\"");
+  }
+}
@@ -1,42 +1,207 @@
-use acari_lib::{internal_error, AcariError, CachedClient, Client, EverhourClient, MiteClient};
+use acari_lib::{internal_error, user_error, AcariError, CachedClient, Client, ClientBuilder, Minutes, Secret};
+use chrono::{NaiveDate, Weekday};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
 use std::fs::{self, File};
 use std::io::{self, Read, Write};
 use std::path::PathBuf;
+use std::process::{Command, Stdio};
 use std::time::Duration;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+const LOCAL_CONFIG_FILE: &str = ".acari.toml";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ValueEnum)]
 #[serde(rename_all = "lowercase")]
 pub enum ClientType {
   Mite,
   Everhour,
 }
 
+impl ClientType {
+  /// Default domain of the backend if there is one that fits (almost) everybody.
+  pub fn default_domain(self) -> Option<&'static str> {
+    match self {
+      ClientType::Mite => None,
+      ClientType::Everhour => Some("api.everhour.com"),
+    }
+  }
+}
+
 impl Default for ClientType {
   fn default() -> Self {
     ClientType::Mite
   }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ConnectionOptions {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub base_url: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub proxy: Option<String>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub ca_certificates: Vec<PathBuf>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub user_agent: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub connect_timeout_seconds: Option<u64>,
+  /// Timeout of a whole request including reading the response, not of a single read
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub timeout_seconds: Option<u64>,
+}
+
+impl ConnectionOptions {
+  fn client_builder(&self) -> Result<ClientBuilder, AcariError> {
+    let mut builder = ClientBuilder::new();
+
+    if let Some(base_url) = &self.base_url {
+      builder = builder.base_url(base_url.parse()?)?;
+    }
+    if let Some(proxy) = &self.proxy {
+      builder = builder.proxy(proxy.as_str());
+    }
+    for ca_certificate in &self.ca_certificates {
+      builder = builder.root_certificate(ca_certificate);
+    }
+    if let Some(user_agent) = &self.user_agent {
+      builder = builder.user_agent(user_agent.as_str());
+    }
+    if let Some(seconds) = self.connect_timeout_seconds {
+      builder = builder.connect_timeout(Duration::from_secs(seconds));
+    }
+    if let Some(seconds) = self.timeout_seconds {
+      builder = builder.timeout(Duration::from_secs(seconds));
+    }
+
+    Ok(builder)
+  }
+}
+
+/// Where to get the api token from, exactly one of these has to be set.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TokenSource {
+  #[serde(default, skip_serializing_if = "Secret::is_empty")]
+  pub token: Secret,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub token_env: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub token_command: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub token_file: Option<PathBuf>,
+}
+
+impl TokenSource {
+  fn redact(&mut self) {
+    if !self.token.is_empty() {
+      self.token = Secret::new("***");
+    }
+  }
+
+  /// Where the token comes from without revealing it.
+  pub fn describe(&self) -> String {
+    match (&self.token_env, &self.token_command, &self.token_file) {
+      (Some(name), _, _) => format!("environment variable {}", name),
+      (_, Some(command), _) => format!("command '{}'", command),
+      (_, _, Some(path)) => format!("file {}", path.to_string_lossy()),
+      _ if !self.token.is_empty() => "inline".to_string(),
+      _ => "not configured".to_string(),
+    }
+  }
+
+  pub fn resolve(&self) -> Result<Secret, AcariError> {
+    match (!self.token.is_empty(), &self.token_env, &self.token_command, &self.token_file) {
+      (true, None, None, None) => Ok(self.token.clone()),
+      (false, Some(name), None, None) => match env::var(name) {
+        Ok(token) if !token.trim().is_empty() => Ok(Secret::new(token.trim())),
+        _ => Err(user_error!("Environment variable {} for token is not set", name)),
+      },
+      (false, None, Some(command), None) => {
+        let output = shell_command(command).stderr(Stdio::inherit()).output()?;
+        if !output.status.success() {
+          return Err(user_error!("Token command '{}' failed: {}", command, output.status));
+        }
+        let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if token.is_empty() {
+          return Err(user_error!("Token command '{}' did not return a token", command));
+        }
+        Ok(Secret::new(token))
+      }
+      (false, None, None, Some(path)) => {
+        let token = fs::read_to_string(path)
+          .map_err(|err| user_error!("Unable to read token file {}: {}", path.to_string_lossy(), err))?
+          .trim()
+          .to_string();
+        if token.is_empty() {
+          return Err(user_error!("Token file {} is empty", path.to_string_lossy()));
+        }
+        Ok(Secret::new(token))
+      }
+      (false, None, None, None) => Err(user_error!("No token configured, please run 'acari init'")),
+      _ => Err(user_error!("Only one of token, token_env, token_command or token_file may be configured")),
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Profile {
   pub domain: String,
-  pub token: String,
+  #[serde(flatten)]
+  pub credentials: TokenSource,
   #[serde(default)]
   pub client: ClientType,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub cache_ttl_minutes: Option<u64>,
+  #[serde(flatten)]
+  pub connection: ConnectionOptions,
+}
+
+impl Profile {
+  /// Create a client for this profile, `cache_ttl` enables the local cache.
+  pub fn client(&self, cache_ttl: Option<Duration>) -> Result<Box<dyn Client>, AcariError> {
+    let builder = self.connection.client_builder()?;
+    let token = &self.credentials.resolve()?;
+    let domain = &self.domain;
+
+    Ok(match (self.client, cache_ttl) {
+      #[cfg(feature = "mite")]
+      (ClientType::Mite, Some(cache_ttl)) => Box::new(CachedClient::new(builder.build_mite(domain, token)?, cache_ttl)?),
+      #[cfg(feature = "mite")]
+      (ClientType::Mite, None) => Box::new(builder.build_mite(domain, token)?),
+      #[cfg(feature = "everhour")]
+      (ClientType::Everhour, Some(cache_ttl)) => Box::new(CachedClient::new(builder.build_everhour(domain, token)?, cache_ttl)?),
+      #[cfg(feature = "everhour")]
+      (ClientType::Everhour, None) => Box::new(builder.build_everhour(domain, token)?),
+      #[allow(unreachable_patterns)]
+      (disabled, _) => return Err(user_error!("Support for {:?} is not enabled in this build of acari", disabled)),
+    })
+  }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Config {
+  /// Named profile to use if none is selected, otherwise the top level settings are used
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub default_profile: Option<String>,
+  #[serde(default, skip_serializing_if = "String::is_empty")]
   pub domain: String,
-  pub token: String,
+  #[serde(flatten)]
+  pub credentials: TokenSource,
   #[serde(default)]
   pub client: ClientType,
   #[serde(default = "default_cache_ttl")]
   pub cache_ttl_minutes: u64,
+  #[serde(flatten)]
+  pub connection: ConnectionOptions,
   #[serde(default)]
   pub profiles: HashMap<String, Profile>,
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub aliases: BTreeMap<String, Alias>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub recurring: Vec<RecurringRule>,
+  #[serde(skip)]
+  pub local: LocalConfig,
 }
 
 impl Config {
@@ -46,7 +211,9 @@ impl Config {
       Ok(mut file) => {
         let mut content = String::new();
         file.read_to_string(&mut content)?;
-        Ok(Some(toml::from_str::<Config>(&content)?))
+        let mut config = toml::from_str::<Config>(&content)?;
+        config.local = LocalConfig::find()?;
+        Ok(Some(config))
       }
       Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
       Err(err) => Err(err.into()),
@@ -54,28 +221,82 @@ impl Config {
   }
 
   pub fn client(&self, maybe_profile: &Option<String>, cached: bool) -> Result<Box<dyn Client>, AcariError> {
-    let (domain, token, client) = match maybe_profile {
-      Some(profile_name) => {
-        let profile = self
-          .profiles
-          .get(profile_name)
-          .ok_or_else(|| AcariError::UserError(format!("No such profile: {}", profile_name)))?;
-        (&profile.domain, &profile.token, &profile.client)
-      }
-      None => (&self.domain, &self.token, &self.client),
+    let profile = self.profile(&self.profile_name(maybe_profile))?;
+    let cache_ttl = if cached {
+      Some(Duration::from_secs(profile.cache_ttl_minutes.unwrap_or(self.cache_ttl_minutes) * 60))
+    } else {
+      None
     };
-    Ok(match client {
-      ClientType::Mite if cached => Box::new(CachedClient::new(
-        MiteClient::new(domain, token)?,
-        Duration::from_secs(self.cache_ttl_minutes * 60),
-      )?),
-      ClientType::Mite => Box::new(MiteClient::new(domain, token)?),
-      ClientType::Everhour if cached => Box::new(CachedClient::new(
-        EverhourClient::new(domain, token)?,
-        Duration::from_secs(self.cache_ttl_minutes * 60),
-      )?),
-      ClientType::Everhour => Box::new(EverhourClient::new(domain, token)?),
-    })
+
+    profile.client(cache_ttl)
+  }
+
+  /// Name of the profile to use (command line, `.acari.toml`, `default_profile`), `None` refers to the top level settings.
+  pub fn profile_name(&self, maybe_profile: &Option<String>) -> Option<String> {
+    maybe_profile
+      .clone()
+      .or_else(|| self.local.profile.clone())
+      .or_else(|| self.default_profile.clone())
+  }
+
+  pub fn profile(&self, maybe_name: &Option<String>) -> Result<Profile, AcariError> {
+    match maybe_name {
+      Some(name) => self.profiles.get(name).cloned().ok_or_else(|| user_error!("No such profile: {}", name)),
+      None if self.domain.is_empty() => Err(user_error!("No default profile configured, use 'acari profile default <name>'")),
+      None => Ok(self.default_profile()),
+    }
+  }
+
+  pub fn default_profile(&self) -> Profile {
+    Profile {
+      domain: self.domain.clone(),
+      credentials: self.credentials.clone(),
+      client: self.client,
+      cache_ttl_minutes: None,
+      connection: self.connection.clone(),
+    }
+  }
+
+  pub fn set_default_profile(&mut self, profile: Profile) {
+    self.domain = profile.domain;
+    self.credentials = profile.credentials;
+    self.client = profile.client;
+    if let Some(cache_ttl_minutes) = profile.cache_ttl_minutes {
+      self.cache_ttl_minutes = cache_ttl_minutes;
+    }
+    self.connection = profile.connection;
+  }
+
+  /// Move the top level settings into a named profile, which becomes the default one if there is none yet.
+  pub fn move_default_profile(&mut self, name: &str) -> Result<(), AcariError> {
+    if self.domain.is_empty() {
+      return Err(user_error!("No top level settings to move"));
+    }
+    if self.profiles.contains_key(name) {
+      return Err(user_error!("Profile {} already exists", name));
+    }
+    let profile = self.default_profile();
+    self.domain = String::new();
+    self.credentials = TokenSource::default();
+    self.client = ClientType::default();
+    self.connection = ConnectionOptions::default();
+    self.profiles.insert(name.to_string(), profile);
+    if self.default_profile.is_none() {
+      self.default_profile = Some(name.to_string());
+    }
+
+    Ok(())
+  }
+
+  /// Copy of the configuration that is safe to show, i.e. without inline tokens.
+  pub fn redacted(&self) -> Config {
+    let mut config = self.clone();
+
+    config.credentials.redact();
+    for profile in config.profiles.values_mut() {
+      profile.credentials.redact();
+    }
+    config
   }
 
   pub fn write(&self) -> Result<(), Box<dyn std::error::Error>> {
@@ -96,7 +317,97 @@ impl Config {
   }
 }
 
-fn config_file() -> PathBuf {
+/// Short name for a customer/project/service triple, used as `@name`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Alias {
+  pub customer: String,
+  pub project: String,
+  pub service: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub note: Option<String>,
+}
+
+/// Time entry that is booked on a regular schedule, see `acari recurring apply`.
+///
+/// The schedule is modeled after iCalendar recurrence rules: `freq` with an optional `interval`
+/// counted from `start`, restricted to `weekdays` or `month_days` and ending at `until`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RecurringRule {
+  pub customer: String,
+  pub project: String,
+  pub service: String,
+  pub minutes: Minutes,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub note: Option<String>,
+  pub freq: Frequency,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub interval: Option<u32>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub weekdays: Vec<Weekday>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub month_days: Vec<u32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub start: Option<NaiveDate>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub until: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+  Daily,
+  Weekly,
+  Monthly,
+}
+
+/// Per directory settings of a `.acari.toml` in the current or any parent directory.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LocalConfig {
+  #[serde(skip)]
+  pub path: Option<PathBuf>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub profile: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub customer: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub project: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub service: Option<String>,
+}
+
+impl LocalConfig {
+  pub fn find() -> Result<LocalConfig, Box<dyn std::error::Error>> {
+    let current_dir = env::current_dir()?;
+
+    for dir in current_dir.ancestors() {
+      let path = dir.join(LOCAL_CONFIG_FILE);
+      if path.is_file() {
+        let content = fs::read_to_string(&path)?;
+        let mut local = toml::from_str::<LocalConfig>(&content).map_err(|err| user_error!("Invalid {}: {}", path.to_string_lossy(), err))?;
+        local.path = Some(path);
+        return Ok(local);
+      }
+    }
+
+    Ok(LocalConfig::default())
+  }
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+  let mut cmd = Command::new("sh");
+  cmd.arg("-c").arg(command);
+  cmd
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+  let mut cmd = Command::new("cmd");
+  cmd.arg("/C").arg(command);
+  cmd
+}
+
+pub fn config_file() -> PathBuf {
   let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
   dirs::config_dir()
     .map(|configs| configs.join("acari"))
@@ -107,3 +418,104 @@ fn config_file() -> PathBuf {
 fn default_cache_ttl() -> u64 {
   1440
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+
+  #[test]
+  fn test_resolve_token_sources() -> Result<(), AcariError> {
+    let inline = TokenSource {
+      token: Secret::new("inline"),
+      ..Default::default()
+    };
+    assert_eq!(inline.resolve()?.expose(), "inline");
+
+    env::set_var("ACARI_TEST_TOKEN", " from-env \n");
+    let from_env = TokenSource {
+      token_env: Some("ACARI_TEST_TOKEN".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(from_env.resolve()?.expose(), "from-env");
+    let unset_env = TokenSource {
+      token_env: Some("ACARI_TEST_TOKEN_UNSET".to_string()),
+      ..Default::default()
+    };
+    assert!(unset_env.resolve().is_err());
+
+    let mut file = tempfile::NamedTempFile::new()?;
+    writeln!(file, "from-file")?;
+    let from_file = TokenSource {
+      token_file: Some(file.path().to_path_buf()),
+      ..Default::default()
+    };
+    assert_eq!(from_file.resolve()?.expose(), "from-file");
+
+    Ok(())
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_resolve_token_command() -> Result<(), AcariError> {
+    let from_command = TokenSource {
+      token_command: Some("echo x".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(from_command.resolve()?.expose(), "x");
+
+    let failing = TokenSource {
+      token_command: Some("false".to_string()),
+      ..Default::default()
+    };
+    assert!(failing.resolve().is_err());
+    let empty = TokenSource {
+      token_command: Some("true".to_string()),
+      ..Default::default()
+    };
+    assert!(empty.resolve().is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_legacy_config() -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = toml::from_str::<Config>(
+      r#"
+domain = "acme.mite.yo.lk"
+token = "secret"
+cache_ttl_minutes = 60
+"#,
+    )?;
+    let profile = config.profile(&config.profile_name(&None))?;
+    assert_eq!(profile.domain, "acme.mite.yo.lk");
+    assert_eq!(profile.credentials.resolve()?.expose(), "secret");
+    assert_eq!(config.cache_ttl_minutes, 60);
+
+    config.move_default_profile("acme")?;
+    assert_eq!(config.default_profile, Some("acme".to_string()));
+    assert!(config.domain.is_empty());
+    let profile = config.profile(&config.profile_name(&None))?;
+    assert_eq!(profile.domain, "acme.mite.yo.lk");
+    assert_eq!(profile.credentials.resolve()?.expose(), "secret");
+    assert!(config.move_default_profile("other").is_err());
+
+    let reread = toml::from_str::<Config>(&toml::to_string_pretty(&config)?)?;
+    assert!(reread.domain.is_empty());
+    assert_eq!(reread.profile(&reread.profile_name(&None))?.domain, "acme.mite.yo.lk");
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_resolve_requires_exactly_one_source() {
+    assert!(TokenSource::default().resolve().is_err());
+
+    let ambiguous = TokenSource {
+      token: Secret::new("inline"),
+      token_env: Some("ACARI_TEST_TOKEN".to_string()),
+      ..Default::default()
+    };
+    assert!(ambiguous.resolve().is_err());
+  }
+}
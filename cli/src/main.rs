@@ -8,6 +8,9 @@ mod config;
 use commands::OutputFormat;
 use config::Config;
 
+#[cfg(not(any(feature = "mite", feature = "everhour")))]
+compile_error!("At least one backend has to be enabled (features: mite, everhour)");
+
 #[derive(Debug, Parser)]
 #[clap(version = "0.1.10")]
 struct Opts {
@@ -26,60 +29,116 @@ struct Opts {
 
 #[derive(Debug, Subcommand, PartialEq, Eq)]
 enum AcariSubCommand {
-  #[clap(about = "Initialize connection to mite")]
-  Init,
+  #[clap(about = "Initialize connection to mite or everhour")]
+  Init(commands::InitCmd),
   #[clap(about = "Just add a time entry")]
   Add(commands::AddCmd),
+  #[clap(about = "Manage aliases for customer/project/service")]
+  Alias(commands::AliasCmd),
+  #[clap(about = "Apply a timesheet file, creating, updating and deleting entries to match it")]
+  Apply(commands::ApplyCmd),
   #[clap(about = "Check connection to mite")]
   Check,
+  #[clap(about = "Show the configuration")]
+  Config(commands::ConfigCmd),
+  #[clap(about = "Copy time entries from one day or week to another")]
+  Copy(commands::CopyCmd),
   #[clap(about = "Clear the local cache")]
   ClearCache,
   #[clap(about = "List all customers")]
   Customers,
+  #[clap(about = "Delete a time entry")]
+  Delete(commands::DeleteCmd),
+  #[clap(about = "Change time or note of a time entry")]
+  Edit(commands::EditCmd),
+  #[clap(name = "edit-span", about = "Edit all time entries of a date span in $EDITOR")]
+  EditSpan(commands::EditSpanCmd),
   #[clap(about = "Query time entries")]
   Entries(commands::EntriesCmd),
+  #[clap(about = "Export time entries")]
+  Export(commands::ExportCmd),
+  #[clap(about = "Manage profiles")]
+  Profile(commands::ProfileCmd),
   #[clap(about = "List configured profiles")]
   Profiles,
   #[clap(about = "List all projects")]
   Projects(commands::ProjectsCmd),
+  #[clap(about = "Manage and apply recurring time entries")]
+  Recurring(commands::RecurringCmd),
   #[clap(about = "List all services")]
   Services(commands::ServicesCommand),
   #[clap(about = "Set time for a project at specific day")]
   Set(commands::SetCmd),
   #[clap(about = "Start tracking time")]
   Start(commands::StartCmd),
+  #[clap(about = "Show totals of time entries grouped by customer, project, service, note or week")]
+  Summary(commands::SummaryCmd),
   #[clap(about = "Stop current time tracking")]
   Stop,
   #[clap(about = "Show currently tracked time entry")]
   Tracking,
+  #[cfg(feature = "tui")]
+  #[clap(about = "Browse and edit time entries of a week in a full-screen view")]
+  Tui,
+  #[clap(about = "Fill the local cache")]
+  WarmCache,
+  #[clap(about = "Show time per customer/project/service and day as grid")]
+  Week(commands::WeekCmd),
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+  #[cfg(feature = "native-tls")]
   unsafe {
     openssl_probe::init_openssl_env_vars();
   }
 
   let opts: Opts = Opts::parse();
 
-  if opts.subcommand == AcariSubCommand::Init {
+  if let AcariSubCommand::Init(init_cmd) = &opts.subcommand {
     // Init is special
-    commands::init(Config::read()?, &opts.profile)?;
+    init_cmd.run(Config::read()?, &opts.profile)?;
+  } else if let AcariSubCommand::Profile(profile_cmd) = &opts.subcommand {
+    // ... so is profile management, which must not require a working client
+    profile_cmd.run(Config::read()?, &opts.profile, opts.output)?;
+  } else if let AcariSubCommand::Config(config_cmd) = &opts.subcommand {
+    config_cmd.run(Config::read()?, &opts.profile, opts.output)?;
+  } else if opts.subcommand == AcariSubCommand::Profiles {
+    commands::profiles(Config::read()?.unwrap_or_default());
   } else if let Some(config) = Config::read()? {
+    if let AcariSubCommand::Alias(alias_cmd) = &opts.subcommand {
+      if !alias_cmd.needs_client() {
+        // Listing and removing aliases must not resolve the token (which might run a token command)
+        alias_cmd.run_without_client(&config, opts.output)?;
+        return Ok(());
+      }
+    }
     let client = config.client(&opts.profile, !opts.no_cache)?;
     match opts.subcommand {
-      AcariSubCommand::Add(add_cmd) => add_cmd.run(client.as_ref(), opts.output)?,
+      AcariSubCommand::Add(add_cmd) => add_cmd.run(client.as_ref(), &config, opts.output)?,
+      AcariSubCommand::Alias(alias_cmd) => alias_cmd.run(client.as_ref(), &config, opts.output)?,
+      AcariSubCommand::Apply(apply_cmd) => apply_cmd.run(client.as_ref(), &config, opts.output)?,
       AcariSubCommand::Check => commands::check(client.as_ref(), opts.output)?,
+      AcariSubCommand::Copy(copy_cmd) => copy_cmd.run(client.as_ref(), opts.output)?,
       AcariSubCommand::ClearCache => clear_cache()?,
       AcariSubCommand::Customers => commands::customers(client.as_ref(), opts.output)?,
+      AcariSubCommand::Delete(delete_cmd) => delete_cmd.run(client.as_ref(), opts.output)?,
+      AcariSubCommand::Edit(edit_cmd) => edit_cmd.run(client.as_ref(), opts.output)?,
+      AcariSubCommand::EditSpan(edit_span_cmd) => edit_span_cmd.run(client.as_ref(), opts.output)?,
       AcariSubCommand::Entries(entries_cmd) => entries_cmd.run(client.as_ref(), opts.output)?,
-      AcariSubCommand::Profiles => commands::profiles(config),
+      AcariSubCommand::Export(export_cmd) => export_cmd.run(client.as_ref(), opts.output)?,
       AcariSubCommand::Projects(projects_cmd) => projects_cmd.run(client.as_ref(), opts.output)?,
+      AcariSubCommand::Recurring(recurring_cmd) => recurring_cmd.run(client.as_ref(), &config, opts.output)?,
       AcariSubCommand::Services(services_cmd) => services_cmd.run(client.as_ref(), opts.output)?,
-      AcariSubCommand::Set(set_cmd) => set_cmd.run(client.as_ref(), opts.output)?,
-      AcariSubCommand::Start(start_cmd) => start_cmd.run(client.as_ref(), opts.output)?,
+      AcariSubCommand::Set(set_cmd) => set_cmd.run(client.as_ref(), &config, opts.output)?,
+      AcariSubCommand::Start(start_cmd) => start_cmd.run(client.as_ref(), &config, opts.output)?,
+      AcariSubCommand::Summary(summary_cmd) => summary_cmd.run(client.as_ref(), opts.output)?,
       AcariSubCommand::Stop => commands::stop(client.as_ref(), opts.output)?,
       AcariSubCommand::Tracking => commands::tracking(client.as_ref(), opts.output)?,
-      AcariSubCommand::Init => unreachable!(),
+      #[cfg(feature = "tui")]
+      AcariSubCommand::Tui => commands::tui(client.as_ref(), &config)?,
+      AcariSubCommand::WarmCache => commands::warm_cache(client.as_ref())?,
+      AcariSubCommand::Week(week_cmd) => week_cmd.run(client.as_ref(), opts.output)?,
+      AcariSubCommand::Init(_) | AcariSubCommand::Config(_) | AcariSubCommand::Profile(_) | AcariSubCommand::Profiles => unreachable!(),
     }
   } else {
     return Err(AcariError::UserError("Missing configuration, run init first".to_string()).into());
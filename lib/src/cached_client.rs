@@ -1,10 +1,11 @@
 use crate::error::AcariError;
-use crate::model::{Account, Customer, Minutes, Project, Service, TimeEntry, Tracker, User};
+use crate::model::{Account, Capabilities, Customer, Minutes, Project, Service, TimeEntry, Tracker, User};
 use crate::model::{ProjectId, ServiceId, TimeEntryId};
 use crate::query::{DateSpan, Day};
 use crate::Client;
 use serde::de::DeserializeOwned;
 use serde::ser::Serialize;
+use std::collections::HashMap;
 use std::io;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -45,21 +46,32 @@ where
     T: DeserializeOwned + Serialize,
     F: FnOnce() -> Result<T, AcariError>,
   {
+    match self.read_cache(cache_name)? {
+      Some(data) => Ok(data),
+      None => {
+        let data = fetch_data()?;
+        self.write_cache(cache_name, &data)?;
+        Ok(data)
+      }
+    }
+  }
+
+  fn read_cache<T: DeserializeOwned>(&self, cache_name: &str) -> Result<Option<T>, AcariError> {
     let cache_file = self.cache_dir.join(cache_name);
     let cache_valid = file_age(&cache_file)?.map(|age| age < self.cache_ttl).unwrap_or(false);
 
     if cache_valid {
-      Ok(serde_json::from_reader(File::open(cache_file)?)?)
+      Ok(Some(serde_json::from_reader(File::open(cache_file)?)?))
     } else {
-      match fetch_data() {
-        Ok(data) => {
-          serde_json::to_writer(File::create(cache_file)?, &data)?;
-          Ok(data)
-        }
-        err => err,
-      }
+      Ok(None)
     }
   }
+
+  fn write_cache<T: Serialize>(&self, cache_name: &str, data: &T) -> Result<(), AcariError> {
+    serde_json::to_writer(File::create(self.cache_dir.join(cache_name))?, data)?;
+
+    Ok(())
+  }
 }
 
 impl<C> Client for CachedClient<C>
@@ -70,6 +82,10 @@ where
     self.client.get_domain()
   }
 
+  fn capabilities(&self) -> Capabilities {
+    self.client.capabilities()
+  }
+
   fn get_account(&self) -> Result<Account, AcariError> {
     self.cache_data("account.json", || self.client.get_account())
   }
@@ -87,13 +103,49 @@ where
   }
 
   fn get_services(&self, project_id: &ProjectId) -> Result<Vec<Service>, AcariError> {
-    self.cache_data(&format!("services-{}.json", project_id), || self.client.get_services(project_id))
+    self.cache_data(&services_cache_name(project_id), || self.client.get_services(project_id))
+  }
+
+  fn get_services_for_projects(&self, project_ids: &[ProjectId]) -> Result<HashMap<ProjectId, Vec<Service>>, AcariError> {
+    let mut services_map = HashMap::new();
+    let mut missing = Vec::new();
+
+    for project_id in project_ids {
+      match self.read_cache(&services_cache_name(project_id))? {
+        Some(services) => {
+          services_map.insert(project_id.clone(), services);
+        }
+        None => missing.push(project_id.clone()),
+      }
+    }
+    if !missing.is_empty() {
+      for (project_id, services) in self.client.get_services_for_projects(&missing)? {
+        self.write_cache(&services_cache_name(&project_id), &services)?;
+        services_map.insert(project_id, services);
+      }
+    }
+
+    Ok(services_map)
+  }
+
+  /// Searches are forwarded so that backends with a server-side search (like everhour) get to use it,
+  /// their results are not cached.
+  fn find_services(&self, project_id: &ProjectId, query: &str) -> Result<Vec<Service>, AcariError> {
+    self.client.find_services(project_id, query)
+  }
+
+  fn get_time_entry(&self, entry_id: &TimeEntryId) -> Result<TimeEntry, AcariError> {
+    self.client.get_time_entry(entry_id) // This should not be cached
   }
 
   fn get_time_entries(&self, date_span: DateSpan) -> Result<Vec<TimeEntry>, AcariError> {
     self.client.get_time_entries(date_span) // This should not be cached
   }
 
+  fn get_time_entries_with_tracker(&self, date_span: DateSpan) -> Result<(Vec<TimeEntry>, Tracker), AcariError> {
+    self.client.get_time_entries_with_tracker(date_span) // This should not be cached
+  }
+
   fn create_time_entry(
     &self,
     day: Day,
@@ -124,6 +176,17 @@ where
   fn delete_tracker(&self, entry_id: &TimeEntryId) -> Result<Tracker, AcariError> {
     self.client.delete_tracker(entry_id)
   }
+  fn create_tracker_with_entries(&self, entry_id: &TimeEntryId, known_entries: &[TimeEntry]) -> Result<Tracker, AcariError> {
+    self.client.create_tracker_with_entries(entry_id, known_entries)
+  }
+
+  fn delete_tracker_with_entries(&self, entry_id: &TimeEntryId, known_entries: &[TimeEntry]) -> Result<Tracker, AcariError> {
+    self.client.delete_tracker_with_entries(entry_id, known_entries)
+  }
+}
+
+fn services_cache_name(project_id: &ProjectId) -> String {
+  format!("services-{}.json", project_id)
 }
 
 fn file_age(path: &Path) -> Result<Option<Duration>, AcariError> {
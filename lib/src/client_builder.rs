@@ -0,0 +1,173 @@
+use crate::error::AcariError;
+#[cfg(feature = "everhour")]
+use crate::everhour_client::EverhourClient;
+#[cfg(feature = "mite")]
+use crate::mite_client::MiteClient;
+#[cfg(any(feature = "mite", feature = "everhour"))]
+use crate::secret::Secret;
+use reqwest::blocking;
+use std::path::PathBuf;
+use std::time::Duration;
+use url::Url;
+
+pub(crate) const USER_AGENT: &str = "acari-lib (https://github.com/untoldwind/acari)";
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connection settings shared by all backends.
+///
+/// By default a connect timeout of 10 seconds and the default request timeout of
+/// the underlying http client (30 seconds) are used.
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+  base_url: Option<Url>,
+  connect_timeout: Option<Duration>,
+  timeout: Option<Duration>,
+  proxy: Option<String>,
+  root_certificates: Vec<PathBuf>,
+  user_agent: String,
+}
+
+impl Default for ClientBuilder {
+  fn default() -> Self {
+    ClientBuilder {
+      base_url: None,
+      connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+      timeout: None,
+      proxy: None,
+      root_certificates: vec![],
+      user_agent: USER_AGENT.to_string(),
+    }
+  }
+}
+
+impl ClientBuilder {
+  pub fn new() -> ClientBuilder {
+    Self::default()
+  }
+
+  /// Use a different base url (scheme, host and port) instead of the one derived from the domain.
+  ///
+  /// Credentials are not supported as part of the url, the api token is always passed separately.
+  pub fn base_url(mut self, base_url: Url) -> Result<Self, AcariError> {
+    if !base_url.username().is_empty() || base_url.password().is_some() {
+      return Err(AcariError::UserError("Base url must not contain credentials".to_string()));
+    }
+    self.base_url = Some(base_url);
+    Ok(self)
+  }
+
+  pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+    self.connect_timeout = Some(timeout);
+    self
+  }
+
+  /// Timeout for a complete request, from connecting until the whole response is read.
+  ///
+  /// There is no separate timeout per read, so large responses (e.g. exports of long spans) on a
+  /// slow connection need a correspondingly larger value.
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Send all requests through a HTTP(S) proxy, e.g. `http://proxy.example.com:3128`.
+  pub fn proxy<S: Into<String>>(mut self, proxy: S) -> Self {
+    self.proxy = Some(proxy.into());
+    self
+  }
+
+  /// Trust the certificates of a PEM file in addition to the system roots.
+  pub fn root_certificate<P: Into<PathBuf>>(mut self, path: P) -> Self {
+    self.root_certificates.push(path.into());
+    self
+  }
+
+  pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+    self.user_agent = user_agent.into();
+    self
+  }
+
+  #[cfg(feature = "mite")]
+  pub fn build_mite(&self, domain: &str, token: &Secret) -> Result<MiteClient, AcariError> {
+    Ok(MiteClient::new_with_client(
+      self.build_url(domain)?,
+      token.clone(),
+      self.build_http_client()?,
+      self.user_agent.clone(),
+    ))
+  }
+
+  #[cfg(feature = "everhour")]
+  pub fn build_everhour(&self, domain: &str, token: &Secret) -> Result<EverhourClient, AcariError> {
+    Ok(EverhourClient::new_with_client(
+      self.build_url(domain)?,
+      token.clone(),
+      self.build_http_client()?,
+      self.user_agent.clone(),
+    ))
+  }
+
+  #[allow(dead_code)]
+  fn build_url(&self, domain: &str) -> Result<Url, AcariError> {
+    match &self.base_url {
+      Some(base_url) => Ok(base_url.clone()),
+      None => Ok(format!("https://{}", domain).parse()?),
+    }
+  }
+
+  #[allow(dead_code)]
+  fn build_http_client(&self) -> Result<blocking::Client, AcariError> {
+    let mut builder = blocking::Client::builder().user_agent(self.user_agent.as_str());
+
+    if let Some(timeout) = self.connect_timeout {
+      builder = builder.connect_timeout(timeout);
+    }
+    if let Some(timeout) = self.timeout {
+      builder = builder.timeout(timeout);
+    }
+    if let Some(proxy) = &self.proxy {
+      builder = builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+    }
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    for path in &self.root_certificates {
+      let pem = std::fs::read(path).map_err(|err| AcariError::UserError(format!("Unable to read certificate {}: {}", path.to_string_lossy(), err)))?;
+      for certificate in reqwest::Certificate::from_pem_bundle(&pem)? {
+        builder = builder.add_root_certificate(certificate);
+      }
+    }
+    #[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+    if !self.root_certificates.is_empty() {
+      return Err(AcariError::UserError("Custom root certificates require TLS support".to_string()));
+    }
+
+    Ok(builder.build()?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_build_url() -> Result<(), Box<dyn std::error::Error>> {
+    let url = ClientBuilder::new().build_url("acme.mite.yo.lk")?;
+
+    assert_eq!(url.as_str(), "https://acme.mite.yo.lk/");
+
+    let url = ClientBuilder::new().base_url("http://localhost:8080".parse()?)?.build_url("acme.mite.yo.lk")?;
+
+    assert_eq!(url.as_str(), "http://localhost:8080/");
+    assert!(ClientBuilder::new().base_url("http://secret@localhost:8080".parse()?).is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_invalid_proxy() {
+    let result = ClientBuilder::new().proxy("not a proxy").build_http_client();
+
+    assert!(result.is_err());
+  }
+}
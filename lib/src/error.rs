@@ -12,6 +12,7 @@ pub enum AcariError {
   Json(serde_json::Error),
   Url(url::ParseError),
   Mite(u16, String),
+  NotFound(String),
   UserError(String),
   InternalError(String),
   ParseNum(num::ParseIntError),
@@ -27,6 +28,7 @@ impl fmt::Display for AcariError {
       AcariError::Json(err) => write!(f, "Json error: {}", err),
       AcariError::Url(err) => write!(f, "Url error: {}", err),
       AcariError::Mite(status, error) => write!(f, "Mite error ({}): {}", status, error),
+      AcariError::NotFound(s) => write!(f, "Not found: {}", s),
       AcariError::UserError(s) => write!(f, "User error: {}", s),
       AcariError::InternalError(s) => write!(f, "Internal error: {}", s),
       AcariError::ParseNum(err) => write!(f, "Number error: {}", err),
@@ -63,5 +65,15 @@ acari_error_from!(std::time::SystemTimeError, Time);
 acari_error_from!(serde_json::Error, Json);
 acari_error_from!(url::ParseError, Url);
 acari_error_from!(chrono::format::ParseError, DateFormat);
-acari_error_from!(reqwest::Error, Request);
 acari_error_from!(num::ParseIntError, ParseNum);
+
+impl From<reqwest::Error> for AcariError {
+  fn from(mut err: reqwest::Error) -> AcariError {
+    // Never leak credentials that might have been part of the request url
+    if let Some(url) = err.url_mut() {
+      let _ = url.set_username("");
+      let _ = url.set_password(None);
+    }
+    AcariError::Request(err)
+  }
+}
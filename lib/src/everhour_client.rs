@@ -1,12 +1,17 @@
+use crate::client_builder::{ClientBuilder, USER_AGENT};
 use crate::everhour_model::{
-  build_time_entry_id, date_span_query_param, parse_time_entry_id, EverhourCreateTimeRecord, EverhourError, EverhourTask, EverhourTimeEntry, EverhourTimer,
-  EverhourUser,
+  build_time_entry_id, date_span_query_param, parse_time_entry_id, EverhourCreateTimeRecord, EverhourCustomer, EverhourError, EverhourTask, EverhourTimeEntry,
+  EverhourTimer, EverhourUser,
+};
+use crate::model::{
+  Account, Capabilities, Customer, CustomerId, Minutes, Project, ProjectId, Service, ServiceId, TimeEntry, TimeEntryId, Tracker, User, UserId,
 };
-use crate::model::{Account, Customer, CustomerId, Minutes, Project, ProjectId, Service, ServiceId, TimeEntry, TimeEntryId, Tracker, User};
 use crate::query::{DateSpan, Day};
+use crate::secret::Secret;
 use crate::Client;
 use crate::{error::AcariError, everhour_model::EverhourProject};
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use reqwest::{blocking, header, Method, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::ser::Serialize;
@@ -14,23 +19,29 @@ use serde_json::json;
 use std::collections::HashMap;
 use url::Url;
 
-const USER_AGENT: &str = "acari-lib (https://github.com/untoldwind/acari)";
-
 #[derive(Debug)]
 pub struct EverhourClient {
   base_url: Url,
+  token: Secret,
   client: blocking::Client,
+  user_agent: String,
 }
 
 impl EverhourClient {
-  pub fn new(domain: &str, token: &str) -> Result<EverhourClient, AcariError> {
-    Ok(Self::new_form_url(format!("https://{}@{}", token, domain).parse()?))
+  pub fn new(domain: &str, token: &Secret) -> Result<EverhourClient, AcariError> {
+    ClientBuilder::new().build_everhour(domain, token)
+  }
+
+  pub fn new_form_url(base_url: Url, token: Secret) -> EverhourClient {
+    Self::new_with_client(base_url, token, blocking::Client::new(), USER_AGENT.to_string())
   }
 
-  pub fn new_form_url(base_url: Url) -> EverhourClient {
+  pub(crate) fn new_with_client(base_url: Url, token: Secret, client: blocking::Client, user_agent: String) -> EverhourClient {
     EverhourClient {
       base_url,
-      client: blocking::Client::new(),
+      token,
+      client,
+      user_agent,
     }
   }
 
@@ -39,9 +50,9 @@ impl EverhourClient {
       self
         .client
         .request(method, self.base_url.join(uri)?.as_str())
-        .header(header::USER_AGENT, USER_AGENT)
+        .header(header::USER_AGENT, self.user_agent.as_str())
         .header(header::HOST, self.base_url.host_str().unwrap_or(""))
-        .header("X-Api-Key", self.base_url.username()),
+        .header("X-Api-Key", self.token.expose()),
     )
   }
 
@@ -57,6 +68,22 @@ impl EverhourClient {
     Self::handle_response(response)
   }
 
+  fn request_empty_with_body<D: Serialize>(&self, method: Method, uri: &str, data: D) -> Result<(), AcariError> {
+    let response = self.base_request(method, uri)?.json(&data).send()?;
+
+    Self::handle_empty_response(response)
+  }
+
+  fn handle_empty_response(response: blocking::Response) -> Result<(), AcariError> {
+    match response.status() {
+      StatusCode::OK | StatusCode::CREATED | StatusCode::NO_CONTENT => Ok(()),
+      status => match response.json::<EverhourError>() {
+        Ok(err) => Err(AcariError::Mite(err.code, err.message)),
+        _ => Err(AcariError::Mite(status.as_u16(), status.to_string())),
+      },
+    }
+  }
+
   fn handle_response<T: DeserializeOwned>(response: blocking::Response) -> Result<T, AcariError> {
     match response.status() {
       StatusCode::OK | StatusCode::CREATED => Ok(response.json()?),
@@ -67,11 +94,43 @@ impl EverhourClient {
     }
   }
 
+  fn project_map(&self) -> Result<HashMap<ProjectId, Project>, AcariError> {
+    let clients = self.request::<Vec<EverhourCustomer>>(Method::GET, "/clients")?;
+
+    Ok(
+      self
+        .request::<Vec<EverhourProject>>(Method::GET, "/projects")?
+        .into_iter()
+        .map(|p| (p.id.clone(), p.into_project(&clients)))
+        .collect(),
+    )
+  }
+
+  fn delete_time_record(&self, user_id: UserId, service_id: ServiceId, date: NaiveDate) -> Result<(), AcariError> {
+    let result = self.request_empty_with_body(
+      Method::DELETE,
+      &format!("/tasks/{}/time", service_id.path_encoded()),
+      json!({
+        "date": date,
+        "user": user_id,
+      }),
+    );
+
+    match result {
+      Err(AcariError::Mite(404, _)) => Err(AcariError::NotFound(format!("No time record for task {} on {}", service_id, date))),
+      result => result,
+    }
+  }
+
   fn entry_from_timer(&self, timer: EverhourTimer) -> Result<Option<TimeEntry>, AcariError> {
     match (timer.status.as_str(), timer.task, timer.user) {
       ("active", maybe_task, Some(user)) => {
         let maybe_project = match maybe_task.as_ref().and_then(|task| task.projects.get(0)) {
-          Some(project_id) => Some(self.request::<EverhourProject>(Method::GET, &format!("/projects/{}", project_id.path_encoded()))?),
+          Some(project_id) => {
+            let clients = self.request::<Vec<EverhourCustomer>>(Method::GET, "/clients")?;
+            let project = self.request::<EverhourProject>(Method::GET, &format!("/projects/{}", project_id.path_encoded()))?;
+            Some(project.into_project(&clients))
+          }
           None => None,
         };
         let minutes = Minutes((timer.duration.unwrap_or_default() + timer.today.unwrap_or_default()) / 60);
@@ -83,8 +142,8 @@ impl EverhourClient {
           ),
           date_at: timer.started_at.naive_utc().date(),
           minutes,
-          customer_id: maybe_project.as_ref().map(|p| p.workspace_id.clone()).unwrap_or_default(),
-          customer_name: maybe_project.as_ref().map(|p| p.workspace_name.clone()).unwrap_or_default(),
+          customer_id: maybe_project.as_ref().map(|p| p.customer_id.clone()).unwrap_or_default(),
+          customer_name: maybe_project.as_ref().map(|p| p.customer_name.clone()).unwrap_or_default(),
           project_id: maybe_project.as_ref().map(|p| p.id.clone()).unwrap_or_default(),
           project_name: maybe_project.as_ref().map(|p| p.name.clone()).unwrap_or_default(),
           service_id: maybe_task.as_ref().map(|task| task.id.clone()).unwrap_or_default(),
@@ -107,6 +166,17 @@ impl Client for EverhourClient {
     self.base_url.host_str().unwrap_or("").to_owned()
   }
 
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      tracker: true,
+      notes: true,
+      billable: false,
+      multiple_entries_per_day: false,
+      locking: true,
+      team_queries: true,
+    }
+  }
+
   fn get_account(&self) -> Result<Account, AcariError> {
     Ok(self.request::<EverhourUser>(Method::GET, "/users/me")?.into())
   }
@@ -116,10 +186,15 @@ impl Client for EverhourClient {
   }
 
   fn get_customers(&self) -> Result<Vec<Customer>, AcariError> {
+    let clients = self.request::<Vec<EverhourCustomer>>(Method::GET, "/clients")?;
     let projects = self.request::<Vec<EverhourProject>>(Method::GET, "/projects")?;
     let mut customers_map: HashMap<CustomerId, Customer> = HashMap::new();
 
+    // Projects without a client are grouped by their workspace
     for project in projects {
+      if clients.iter().any(|c| c.projects.contains(&project.id)) {
+        continue;
+      }
       let created_at = project.created_at;
       let archived = project.status != "open";
       let customer_ref = customers_map.entry(project.workspace_id.clone()).or_insert_with(|| project.into());
@@ -132,13 +207,14 @@ impl Client for EverhourClient {
       }
     }
 
-    Ok(customers_map.into_iter().map(|(_, v)| v).collect())
+    Ok(clients.into_iter().map(Into::into).chain(customers_map.into_values()).collect())
   }
 
   fn get_projects(&self) -> Result<Vec<Project>, AcariError> {
+    let clients = self.request::<Vec<EverhourCustomer>>(Method::GET, "/clients")?;
     let projects = self.request::<Vec<EverhourProject>>(Method::GET, "/projects")?;
 
-    Ok(projects.into_iter().map(Into::into).collect())
+    Ok(projects.into_iter().map(|p| p.into_project(&clients)).collect())
   }
 
   fn get_services(&self, project_id: &ProjectId) -> Result<Vec<Service>, AcariError> {
@@ -147,13 +223,36 @@ impl Client for EverhourClient {
     Ok(tasks.into_iter().map(Into::into).collect())
   }
 
+  fn find_services(&self, project_id: &ProjectId, query: &str) -> Result<Vec<Service>, AcariError> {
+    let tasks = self.request::<Vec<EverhourTask>>(
+      Method::GET,
+      &format!(
+        "/projects/{}/tasks/search?query={}",
+        project_id.path_encoded(),
+        utf8_percent_encode(query, NON_ALPHANUMERIC)
+      ),
+    )?;
+
+    Ok(tasks.into_iter().map(Into::into).collect())
+  }
+
+  fn get_time_entry(&self, entry_id: &TimeEntryId) -> Result<TimeEntry, AcariError> {
+    // Ids of time records are always composed of user, task and date
+    if let TimeEntryId::Num(_) = entry_id {
+      return Err(AcariError::NotFound(format!("No time entry with id {}", entry_id)));
+    }
+    let (_, _, date) = parse_time_entry_id(entry_id)?;
+
+    self
+      .get_time_entries(DateSpan::FromTo(date, date))?
+      .into_iter()
+      .find(|e| e.id == *entry_id)
+      .ok_or_else(|| AcariError::NotFound(format!("No time entry with id {}", entry_id)))
+  }
+
   fn get_time_entries(&self, date_span: DateSpan) -> Result<Vec<TimeEntry>, AcariError> {
     let user = self.request::<EverhourUser>(Method::GET, "/users/me")?;
-    let project_map: HashMap<ProjectId, EverhourProject> = self
-      .request::<Vec<EverhourProject>>(Method::GET, "/projects")?
-      .into_iter()
-      .map(|p| (p.id.clone(), p))
-      .collect();
+    let project_map = self.project_map()?;
     let entries = self.request::<Vec<EverhourTimeEntry>>(Method::GET, &format!("/users/me/time?{}", date_span_query_param(&date_span)))?;
 
     Ok(entries.into_iter().filter_map(|e| e.into_entry(&project_map, &user)).collect())
@@ -161,14 +260,10 @@ impl Client for EverhourClient {
 
   fn create_time_entry(&self, day: Day, _: &ProjectId, service_id: &ServiceId, minutes: Minutes, note: Option<String>) -> Result<TimeEntry, AcariError> {
     let user = self.request::<EverhourUser>(Method::GET, "/users/me")?;
-    let project_map: HashMap<ProjectId, EverhourProject> = self
-      .request::<Vec<EverhourProject>>(Method::GET, "/projects")?
-      .into_iter()
-      .map(|p| (p.id.clone(), p))
-      .collect();
+    let project_map = self.project_map()?;
 
     let entry: EverhourTimeEntry = self.request_with_body(
-      Method::PUT,
+      Method::POST,
       &format!("/tasks/{}/time", service_id.path_encoded()),
       EverhourCreateTimeRecord {
         date: day.as_date(),
@@ -187,43 +282,25 @@ impl Client for EverhourClient {
     let (user_id, service_id, date) = parse_time_entry_id(entry_id)?;
 
     if minutes.0 == 0 {
-      let _: EverhourTimeEntry = self.request_with_body(
-        Method::DELETE,
-        &format!("/tasks/{}/time", service_id.path_encoded()),
-        json!({
-          "date": date,
-          "user": user_id,
-        }),
-      )?;
-    } else {
-      let _: EverhourTimeEntry = self.request_with_body(
-        Method::PUT,
-        &format!("/tasks/{}/time", service_id.path_encoded()),
-        EverhourCreateTimeRecord {
-          date,
-          user: user_id,
-          time: minutes,
-          comment: note.unwrap_or_default(),
-        },
-      )?;
+      return self.delete_time_record(user_id, service_id, date);
     }
 
-    Ok(())
+    self.request_empty_with_body(
+      Method::PUT,
+      &format!("/tasks/{}/time", service_id.path_encoded()),
+      EverhourCreateTimeRecord {
+        date,
+        user: user_id,
+        time: minutes,
+        comment: note.unwrap_or_default(),
+      },
+    )
   }
 
   fn delete_time_entry(&self, entry_id: &TimeEntryId) -> Result<(), AcariError> {
     let (user_id, service_id, date) = parse_time_entry_id(entry_id)?;
 
-    let _: EverhourTimeEntry = self.request_with_body(
-      Method::POST,
-      &format!("/tasks/{}/time", service_id.path_encoded()),
-      json!({
-        "user": user_id,
-        "date": date,
-      }),
-    )?;
-
-    Ok(())
+    self.delete_time_record(user_id, service_id, date)
   }
 
   fn get_tracker(&self) -> Result<Tracker, AcariError> {
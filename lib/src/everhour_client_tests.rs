@@ -1,5 +1,8 @@
-use super::{Account, AccountId, Client, Customer, CustomerId, EverhourClient, Project, ProjectId, Service, ServiceId, User, UserId};
-use chrono::{TimeZone, Utc};
+use super::{
+  AcariError, Account, AccountId, Client, Customer, CustomerId, Day, EverhourClient, Minutes, Project, ProjectId, Secret, Service, ServiceId, TimeEntry,
+  TimeEntryId, User, UserId,
+};
+use chrono::{NaiveDate, TimeZone, Utc};
 use pact_consumer::prelude::*;
 use pact_consumer::term;
 use serde_json::json;
@@ -40,9 +43,7 @@ fn test_get_account() -> Result<(), Box<dyn std::error::Error>> {
     })
     .build();
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = EverhourClient::new_form_url(url);
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let account = client.get_account()?;
 
@@ -93,9 +94,7 @@ fn test_get_myself() -> Result<(), Box<dyn std::error::Error>> {
     })
     .build();
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = EverhourClient::new_form_url(url);
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let user = client.get_myself()?;
 
@@ -119,6 +118,19 @@ fn test_get_myself() -> Result<(), Box<dyn std::error::Error>> {
 #[test]
 fn test_get_customers() -> Result<(), Box<dyn std::error::Error>> {
   let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("get clients", |i| {
+      i.given("User with API token");
+      i.request.get().path("/clients").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!([{
+        "id": 4321,
+        "name": "Client 1",
+        "createdAt": "2021-01-10 09:30:00",
+        "status": "active",
+        "projects": [
+          "as:12346"
+        ],
+      }]));
+    })
     .interaction("get projects", |i| {
       i.given("User with API token");
       i.request.get().path("/projects").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
@@ -157,9 +169,7 @@ fn test_get_customers() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = EverhourClient::new_form_url(url);
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let mut customers = client.get_customers()?;
   customers.sort_by(|c1, c2| c1.name.cmp(&c2.name));
@@ -167,21 +177,21 @@ fn test_get_customers() -> Result<(), Box<dyn std::error::Error>> {
   assert_eq!(customers.len(), 2);
   assert_eq!(
     Customer {
-      id: CustomerId::Str("as:54321".to_string()),
-      name: "Workspace 1".to_string(),
+      id: CustomerId::Num(4321),
+      name: "Client 1".to_string(),
       note: "".to_string(),
       archived: false,
-      created_at: Utc.ymd(2021, 01, 14).and_hms(00, 00, 00),
+      created_at: Utc.ymd(2021, 01, 10).and_hms(09, 30, 00),
     },
     customers[0]
   );
   assert_eq!(
     Customer {
-      id: CustomerId::Str("as:54322".to_string()),
-      name: "Workspace 2".to_string(),
+      id: CustomerId::Str("as:54321".to_string()),
+      name: "Workspace 1".to_string(),
       note: "".to_string(),
       archived: false,
-      created_at: Utc.ymd(2021, 01, 15).and_hms(00, 00, 00),
+      created_at: Utc.ymd(2021, 01, 14).and_hms(00, 00, 00),
     },
     customers[1]
   );
@@ -192,6 +202,19 @@ fn test_get_customers() -> Result<(), Box<dyn std::error::Error>> {
 #[test]
 fn test_get_projects() -> Result<(), Box<dyn std::error::Error>> {
   let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("get clients", |i| {
+      i.given("User with API token");
+      i.request.get().path("/clients").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!([{
+        "id": 4321,
+        "name": "Client 1",
+        "createdAt": "2021-01-10 09:30:00",
+        "status": "active",
+        "projects": [
+          "as:12346"
+        ],
+      }]));
+    })
     .interaction("get projects", |i| {
       i.given("User with API token");
       i.request.get().path("/projects").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
@@ -230,9 +253,7 @@ fn test_get_projects() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = EverhourClient::new_form_url(url);
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let projects = client.get_projects()?;
 
@@ -254,8 +275,8 @@ fn test_get_projects() -> Result<(), Box<dyn std::error::Error>> {
       id: ProjectId::Str("as:12346".to_string()),
       name: "Project 2".to_string(),
       note: "".to_string(),
-      customer_id: CustomerId::Str("as:54322".to_string()),
-      customer_name: "Workspace 2".to_string(),
+      customer_id: CustomerId::Num(4321),
+      customer_name: "Client 1".to_string(),
       archived: false,
       created_at: Utc.ymd(2021, 01, 15).and_hms(00, 00, 00),
     },
@@ -295,6 +316,13 @@ fn test_get_services() -> Result<(), Box<dyn std::error::Error>> {
         "projects": [
           "as:8353429"
         ],
+        "estimate": {
+          "total": 7200,
+          "type": "overall",
+        },
+        "time": {
+          "total": 5400,
+        },
       }, {
         "id": "as:123451235",
         "name": "Task 2",
@@ -309,9 +337,7 @@ fn test_get_services() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = EverhourClient::new_form_url(url);
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let services = client.get_services(&ProjectId::Str("as:12345".to_string()))?;
 
@@ -320,9 +346,12 @@ fn test_get_services() -> Result<(), Box<dyn std::error::Error>> {
     Service {
       id: ServiceId::Str("as:123451234".to_string()),
       name: "Task 1".to_string(),
-      note: "Untitled section".to_string(),
+      note: "".to_string(),
       archived: true,
       billable: true,
+      section: Some("Untitled section".to_string()),
+      estimate: Some(Minutes(120)),
+      time_spent: Some(Minutes(90)),
       created_at: Utc.ymd(2021, 01, 18).and_hms(12, 55, 55),
     },
     services[0]
@@ -331,9 +360,12 @@ fn test_get_services() -> Result<(), Box<dyn std::error::Error>> {
     Service {
       id: ServiceId::Str("as:123451235".to_string()),
       name: "Task 2".to_string(),
-      note: "Untitled section".to_string(),
+      note: "".to_string(),
       archived: false,
       billable: true,
+      section: Some("Untitled section".to_string()),
+      estimate: None,
+      time_spent: None,
       created_at: Utc.ymd(2021, 01, 25).and_hms(11, 54, 28),
     },
     services[1]
@@ -341,3 +373,383 @@ fn test_get_services() -> Result<(), Box<dyn std::error::Error>> {
 
   Ok(())
 }
+
+#[test]
+fn test_find_services() -> Result<(), Box<dyn std::error::Error>> {
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("search project tasks", |i| {
+      i.given("User with API token");
+      i.request
+        .get()
+        .path("/projects/as%3A12345/tasks/search")
+        .query_param("query", "Task 2")
+        .header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!([{
+        "id": "as:123451235",
+        "name": "Task 2",
+        "iteration": "Second section",
+        "createdAt": "2021-01-25 11:54:28",
+        "status": "open",
+        "projects": [
+          "as:12345"
+        ],
+      }]));
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  let services = client.find_services(&ProjectId::Str("as:12345".to_string()), "Task 2")?;
+
+  assert_eq!(services.len(), 1);
+  assert_eq!(
+    Service {
+      id: ServiceId::Str("as:123451235".to_string()),
+      name: "Task 2".to_string(),
+      note: "".to_string(),
+      archived: false,
+      billable: true,
+      section: Some("Second section".to_string()),
+      estimate: None,
+      time_spent: None,
+      created_at: Utc.ymd(2021, 01, 25).and_hms(11, 54, 28),
+    },
+    services[0]
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_create_entry() -> Result<(), Box<dyn std::error::Error>> {
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("get myself for time record", |i| {
+      i.given("User with API token");
+      i.request.get().path("/users/me").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!({
+            "id": 12345,
+            "name": "August Ausgedacht",
+            "email": "august.ausgedacht@demo.de",
+            "status": "active",
+            "role": "member",
+            "headline": "",
+            "isSuspended": false,
+            "createdAt": "2021-01-29 12:00:50",
+            "team": {
+                "id": 1234,
+                "name": "Demo GmbH",
+                "createdAt": "2021-01-14 18:59:59",
+                "currencyDetails": {
+                  "code": "EUR",
+                  "name": "Euro",
+                },
+            },
+      }));
+    })
+    .interaction("get clients for time record", |i| {
+      i.given("User with API token");
+      i.request.get().path("/clients").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!([]));
+    })
+    .interaction("get projects for time record", |i| {
+      i.given("User with API token");
+      i.request.get().path("/projects").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!([{
+        "id": "as:12345",
+        "platform": "as",
+        "name": "Project 1",
+        "createdAt": "2021-01-14",
+        "workspaceId": "as:54321",
+        "workspaceName": "Workspace 1",
+        "foreign": false,
+        "status": "open",
+        "estimatesType": "any",
+      }]));
+    })
+    .interaction("add time record", |i| {
+      i.given("User with API token");
+      i.request
+        .post()
+        .path("/tasks/as%3A123451234/time")
+        .json_body(json!({
+          "date": "2021-02-01",
+          "time": 5400,
+          "user": 12345,
+          "comment": "Some note",
+        }))
+        .header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.created().json_utf8().json_body(json!({
+        "date": "2021-02-01",
+        "comment": "Some note",
+        "task": {
+          "id": "as:123451234",
+          "name": "Task 1",
+          "iteration": "Untitled section",
+          "createdAt": "2021-01-18 12:55:55",
+          "status": "open",
+          "projects": [
+            "as:12345"
+          ],
+        },
+        "time": 5400,
+        "user": 12345,
+        "isLocked": false,
+        "createdAt": "2021-02-01 10:11:12",
+      }));
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  let entry = client.create_time_entry(
+    Day::Date(NaiveDate::from_ymd(2021, 2, 1)),
+    &ProjectId::Str("as:12345".to_string()),
+    &ServiceId::Str("as:123451234".to_string()),
+    Minutes(90),
+    Some("Some note".to_string()),
+  )?;
+
+  assert_eq!(
+    TimeEntry {
+      id: TimeEntryId::Str("n12345|sas:123451234|2021-02-01".to_string()),
+      date_at: NaiveDate::from_ymd(2021, 2, 1),
+      minutes: Minutes(90),
+      customer_id: CustomerId::Str("as:54321".to_string()),
+      customer_name: "Workspace 1".to_string(),
+      project_id: ProjectId::Str("as:12345".to_string()),
+      project_name: "Project 1".to_string(),
+      service_id: ServiceId::Str("as:123451234".to_string()),
+      service_name: "Task 1".to_string(),
+      user_id: UserId::Num(12345),
+      user_name: "August Ausgedacht".to_string(),
+      note: "Some note".to_string(),
+      billable: true,
+      locked: false,
+      created_at: Utc.ymd(2021, 2, 1).and_hms(10, 11, 12),
+    },
+    entry
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_get_entry() -> Result<(), Box<dyn std::error::Error>> {
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("get myself for time entry", |i| {
+      i.given("User with API token");
+      i.request.get().path("/users/me").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!({
+            "id": 12345,
+            "name": "August Ausgedacht",
+            "email": "august.ausgedacht@demo.de",
+            "status": "active",
+            "role": "member",
+            "headline": "",
+            "isSuspended": false,
+            "createdAt": "2021-01-29 12:00:50",
+            "team": {
+                "id": 1234,
+                "name": "Demo GmbH",
+                "createdAt": "2021-01-14 18:59:59",
+                "currencyDetails": {
+                  "code": "EUR",
+                  "name": "Euro",
+                },
+            },
+      }));
+    })
+    .interaction("get clients for time entry", |i| {
+      i.given("User with API token");
+      i.request.get().path("/clients").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!([]));
+    })
+    .interaction("get projects for time entry", |i| {
+      i.given("User with API token");
+      i.request.get().path("/projects").header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!([{
+        "id": "as:12345",
+        "platform": "as",
+        "name": "Project 1",
+        "createdAt": "2021-01-14",
+        "workspaceId": "as:54321",
+        "workspaceName": "Workspace 1",
+        "foreign": false,
+        "status": "open",
+        "estimatesType": "any",
+      }]));
+    })
+    .interaction("get time of day", |i| {
+      i.given("User with API token");
+      i.request
+        .get()
+        .path("/users/me/time")
+        .query_param("from", "2021-02-01")
+        .query_param("to", "2021-02-01")
+        .header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!([{
+        "date": "2021-02-01",
+        "comment": "Some note",
+        "task": {
+          "id": "as:123451234",
+          "name": "Task 1",
+          "iteration": "Untitled section",
+          "createdAt": "2021-01-18 12:55:55",
+          "status": "open",
+          "projects": [
+            "as:12345"
+          ],
+        },
+        "time": 5400,
+        "user": 12345,
+        "isLocked": true,
+        "createdAt": "2021-02-01 10:11:12",
+      }]));
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  let entry = client.get_time_entry(&TimeEntryId::Str("n12345|sas:123451234|2021-02-01".to_string()))?;
+
+  assert_eq!(
+    TimeEntry {
+      id: TimeEntryId::Str("n12345|sas:123451234|2021-02-01".to_string()),
+      date_at: NaiveDate::from_ymd(2021, 2, 1),
+      minutes: Minutes(90),
+      customer_id: CustomerId::Str("as:54321".to_string()),
+      customer_name: "Workspace 1".to_string(),
+      project_id: ProjectId::Str("as:12345".to_string()),
+      project_name: "Project 1".to_string(),
+      service_id: ServiceId::Str("as:123451234".to_string()),
+      service_name: "Task 1".to_string(),
+      user_id: UserId::Num(12345),
+      user_name: "August Ausgedacht".to_string(),
+      note: "Some note".to_string(),
+      billable: true,
+      locked: true,
+      created_at: Utc.ymd(2021, 2, 1).and_hms(10, 11, 12),
+    },
+    entry
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_update_entry() -> Result<(), Box<dyn std::error::Error>> {
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("update time record", |i| {
+      i.given("User with API token");
+      i.request
+        .put()
+        .path("/tasks/as%3A123451234/time")
+        .json_body(json!({
+          "date": "2021-02-01",
+          "time": 7200,
+          "user": 12345,
+          "comment": "",
+        }))
+        .header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!({
+        "date": "2021-02-01",
+        "comment": "",
+        "time": 7200,
+        "user": 12345,
+        "isLocked": false,
+        "createdAt": "2021-02-01 10:11:12",
+      }));
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  client.update_time_entry(&TimeEntryId::Str("n12345|sas:123451234|2021-02-01".to_string()), Minutes(120), None)?;
+
+  Ok(())
+}
+
+#[test]
+fn test_update_entry_to_zero() -> Result<(), Box<dyn std::error::Error>> {
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("delete time record on zero update", |i| {
+      i.given("User with API token");
+      i.request
+        .delete()
+        .path("/tasks/as%3A123451234/time")
+        .json_body(json!({
+          "date": "2021-02-01",
+          "user": 12345,
+        }))
+        .header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.no_content();
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  client.update_time_entry(&TimeEntryId::Str("n12345|sas:123451234|2021-02-01".to_string()), Minutes(0), None)?;
+
+  Ok(())
+}
+
+#[test]
+fn test_delete_entry() -> Result<(), Box<dyn std::error::Error>> {
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("delete time record", |i| {
+      i.given("User with API token");
+      i.request
+        .delete()
+        .path("/tasks/as%3A123451234/time")
+        .json_body(json!({
+          "date": "2021-02-01",
+          "user": 12345,
+        }))
+        .header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.no_content();
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  client.delete_time_entry(&TimeEntryId::Str("n12345|sas:123451234|2021-02-01".to_string()))?;
+
+  Ok(())
+}
+
+#[test]
+fn test_delete_entry_not_found() -> Result<(), Box<dyn std::error::Error>> {
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("delete missing time record", |i| {
+      i.given("User with API token");
+      i.request
+        .delete()
+        .path("/tasks/as%3A123451234/time")
+        .json_body(json!({
+          "date": "2021-02-02",
+          "user": 12345,
+        }))
+        .header("X-Api-Key", term!("[0-9a-f]+", "12345678"));
+      i.response.not_found().json_utf8().json_body(json!({
+        "code": 404,
+        "message": "Time record not found",
+      }));
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = EverhourClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  match client.delete_time_entry(&TimeEntryId::Str("n12345|sas:123451234|2021-02-02".to_string())) {
+    Err(AcariError::NotFound(_)) => (),
+    result => panic!("Expected not found, got: {:?}", result),
+  }
+
+  Ok(())
+}
@@ -2,10 +2,9 @@ use std::collections::HashMap;
 
 use crate::{
   model::{Account, AccountId, Customer, CustomerId, Minutes, Project, ProjectId, Service, ServiceId, TimeEntry, TimeEntryId, User, UserId},
-  AcariError, DateSpan, Day,
+  AcariError, DateSpan,
 };
-use chrono::offset::Local;
-use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -84,6 +83,19 @@ pub struct EverhourProject {
   pub created_at: DateTime<Utc>,
 }
 
+impl EverhourProject {
+  pub fn into_project(self, clients: &[EverhourCustomer]) -> Project {
+    match clients.iter().find(|c| c.projects.contains(&self.id)) {
+      Some(client) => Project {
+        customer_id: client.id.clone(),
+        customer_name: client.name.clone(),
+        ..self.into()
+      },
+      None => self.into(),
+    }
+  }
+}
+
 impl From<EverhourProject> for Customer {
   fn from(f: EverhourProject) -> Self {
     Customer {
@@ -110,26 +122,65 @@ impl From<EverhourProject> for Project {
   }
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EverhourCustomer {
+  pub id: CustomerId,
+  pub name: String,
+  #[serde(default)]
+  pub status: String,
+  #[serde(default)]
+  pub projects: Vec<ProjectId>,
+  #[serde(with = "date_format")]
+  pub created_at: DateTime<Utc>,
+}
+
+impl From<EverhourCustomer> for Customer {
+  fn from(f: EverhourCustomer) -> Self {
+    Customer {
+      id: f.id,
+      name: f.name,
+      note: "".to_string(),
+      archived: f.status == "archived",
+      created_at: f.created_at,
+    }
+  }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct EverhourTask {
   pub id: ServiceId,
   pub name: String,
   pub status: String,
+  #[serde(default)]
   pub iteration: String,
   pub projects: Vec<ProjectId>,
+  #[serde(default)]
+  pub estimate: Option<EverhourTaskTime>,
+  #[serde(default)]
+  pub time: Option<EverhourTaskTime>,
   #[serde(with = "date_format")]
   pub created_at: DateTime<Utc>,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct EverhourTaskTime {
+  #[serde(with = "minutes_in_seconds")]
+  pub total: Minutes,
+}
+
 impl From<EverhourTask> for Service {
   fn from(f: EverhourTask) -> Self {
     Service {
       id: f.id,
       name: f.name,
-      note: f.iteration,
+      note: "".to_string(),
       archived: f.status != "open",
       billable: true,
+      section: Some(f.iteration).filter(|s| !s.is_empty()),
+      estimate: f.estimate.map(|e| e.total),
+      time_spent: f.time.map(|t| t.total),
       created_at: f.created_at,
     }
   }
@@ -151,15 +202,15 @@ pub struct EverhourTimeEntry {
 }
 
 impl EverhourTimeEntry {
-  pub fn into_entry(self, project_map: &HashMap<ProjectId, EverhourProject>, user: &EverhourUser) -> Option<TimeEntry> {
+  pub fn into_entry(self, project_map: &HashMap<ProjectId, Project>, user: &EverhourUser) -> Option<TimeEntry> {
     match self.task {
       Some(task) => match task.projects.iter().filter_map(|p| project_map.get(p)).next() {
         Some(project) => Some(TimeEntry {
           id: build_time_entry_id(&self.user, task.id.clone(), &self.date),
           date_at: self.date,
           minutes: self.time,
-          customer_id: project.workspace_id.clone(),
-          customer_name: project.workspace_name.clone(),
+          customer_id: project.customer_id.clone(),
+          customer_name: project.customer_name.clone(),
           project_id: project.id.clone(),
           project_name: project.name.clone(),
           service_id: task.id,
@@ -229,58 +280,10 @@ fn default_started_at() -> DateTime<Utc> {
   Utc::now()
 }
 
-pub fn day_query_param(day: &Day) -> String {
-  match day {
-    Day::Today => format!("{}", Local::now().naive_local().date()),
-    Day::Yesterday => format!("{}", Local::now().naive_local().date().pred()),
-    Day::Date(date) => format!("{}", date),
-  }
-}
-
 pub fn date_span_query_param(span: &DateSpan) -> String {
-  match span {
-    DateSpan::ThisWeek => {
-      let now = Local::now().naive_local().date();
-      let year = now.year();
-      let week = now.iso_week().week();
-
-      let mon = NaiveDate::from_isoywd(year, week, Weekday::Mon);
-      let sun = NaiveDate::from_isoywd(year, week, Weekday::Sun);
+  let (from, to) = span.date_range();
 
-      format!("from={}&to={}", mon, sun)
-    }
-    DateSpan::LastWeek => {
-      let now = Local::now().naive_local().date();
-
-      let sun = NaiveDate::from_isoywd(now.year(), now.iso_week().week(), Weekday::Mon).succ();
-      let mon = NaiveDate::from_isoywd(sun.year(), sun.iso_week().week(), Weekday::Mon);
-
-      format!("from={}&to={}", mon, sun)
-    }
-    DateSpan::ThisMonth => {
-      let now = Local::now().naive_local().date();
-      let year = now.year();
-      let month = now.month();
-
-      let start = NaiveDate::from_ymd(year, month, 1);
-      let end = if month == 12 {
-        NaiveDate::from_ymd(year + 1, 1, 1).pred()
-      } else {
-        NaiveDate::from_ymd(year, month + 1, 1).pred()
-      };
-
-      format!("from={}&to={}", start, end)
-    }
-    DateSpan::LastMonth => {
-      let now = Local::now().naive_local().date();
-      let end = NaiveDate::from_ymd(now.year(), now.month(), 1).pred();
-      let start = NaiveDate::from_ymd(end.year(), end.month(), 1);
-
-      format!("from={}&to={}", start, end)
-    }
-    DateSpan::Day(date) => format!("from={}&to={}", day_query_param(&date), day_query_param(&date)),
-    DateSpan::FromTo(from, to) => format!("from={}&to={}", from, to),
-  }
+  format!("from={}&to={}", from, to)
 }
 
 pub fn build_time_entry_id(user_id: &UserId, service_id: ServiceId, date: &NaiveDate) -> TimeEntryId {
@@ -348,3 +351,24 @@ mod minutes_in_seconds {
     Ok(Minutes(seconds / 60))
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Day;
+
+  #[test]
+  fn test_date_span_query_param() {
+    let date = NaiveDate::from_ymd_opt(2024, 12, 30).unwrap();
+
+    assert_eq!(date_span_query_param(&DateSpan::Day(Day::Date(date))), "from=2024-12-30&to=2024-12-30");
+    assert_eq!(
+      date_span_query_param(&DateSpan::FromTo(date, NaiveDate::from_ymd_opt(2025, 1, 5).unwrap())),
+      "from=2024-12-30&to=2025-01-05"
+    );
+    for span in &[DateSpan::ThisWeek, DateSpan::LastWeek, DateSpan::ThisMonth, DateSpan::LastMonth] {
+      let (from, to) = span.date_range();
+      assert_eq!(date_span_query_param(span), format!("from={}&to={}", from, to));
+    }
+  }
+}
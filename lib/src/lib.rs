@@ -1,29 +1,47 @@
 mod cached_client;
+mod client_builder;
 mod error;
+#[cfg(feature = "everhour")]
 mod everhour_client;
+#[cfg(feature = "everhour")]
 mod everhour_model;
+#[cfg(feature = "mite")]
 mod mite_client;
+#[cfg(feature = "mite")]
 mod mite_model;
+#[cfg(any(test, feature = "testing"))]
+mod mock_client;
 mod model;
+mod parallel;
 mod query;
+mod secret;
 
 pub use cached_client::{clear_cache, CachedClient};
+pub use client_builder::ClientBuilder;
 pub use error::AcariError;
+#[cfg(feature = "everhour")]
 pub use everhour_client::EverhourClient;
+#[cfg(feature = "mite")]
 pub use mite_client::MiteClient;
-pub use model::{Account, Customer, Minutes, Project, Service, TimeEntry, Tracker, User};
+#[cfg(any(test, feature = "testing"))]
+pub use mock_client::{MockClient, MockOperation, Mutation};
+pub use model::{Account, Capabilities, Customer, Minutes, Project, Service, TimeEntry, Tracker, User};
 pub use model::{AccountId, CustomerId, ProjectId, ServiceId, TimeEntryId, UserId};
 pub use query::{DateSpan, Day};
+pub use secret::Secret;
+use std::collections::HashMap;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "mite"))]
 mod mite_client_tests;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "everhour"))]
 mod everhour_client_tests;
 
-pub trait Client {
+pub trait Client: Send + Sync {
   fn get_domain(&self) -> String;
 
+  fn capabilities(&self) -> Capabilities;
+
   fn get_account(&self) -> Result<Account, AcariError>;
 
   fn get_myself(&self) -> Result<User, AcariError>;
@@ -34,8 +52,32 @@ pub trait Client {
 
   fn get_services(&self, project_id: &ProjectId) -> Result<Vec<Service>, AcariError>;
 
+  fn get_services_for_projects(&self, project_ids: &[ProjectId]) -> Result<HashMap<ProjectId, Vec<Service>>, AcariError> {
+    let services = parallel::map_parallel(project_ids, |project_id| self.get_services(project_id))?;
+
+    Ok(project_ids.iter().cloned().zip(services).collect())
+  }
+
+  fn find_services(&self, project_id: &ProjectId, query: &str) -> Result<Vec<Service>, AcariError> {
+    let query = query.to_lowercase();
+
+    Ok(
+      self
+        .get_services(project_id)?
+        .into_iter()
+        .filter(|s| s.name.to_lowercase().contains(&query))
+        .collect(),
+    )
+  }
+
+  fn get_time_entry(&self, entry_id: &TimeEntryId) -> Result<TimeEntry, AcariError>;
+
   fn get_time_entries(&self, date_span: DateSpan) -> Result<Vec<TimeEntry>, AcariError>;
 
+  fn get_time_entries_with_tracker(&self, date_span: DateSpan) -> Result<(Vec<TimeEntry>, Tracker), AcariError> {
+    Ok((self.get_time_entries(date_span)?, self.get_tracker()?))
+  }
+
   fn create_time_entry(
     &self,
     day: Day,
@@ -54,6 +96,16 @@ pub trait Client {
   fn create_tracker(&self, entry_id: &TimeEntryId) -> Result<Tracker, AcariError>;
 
   fn delete_tracker(&self, entry_id: &TimeEntryId) -> Result<Tracker, AcariError>;
+
+  /// Start tracking, time entries already known to the caller are used to resolve the tracker instead of fetching them again.
+  fn create_tracker_with_entries(&self, entry_id: &TimeEntryId, _known_entries: &[TimeEntry]) -> Result<Tracker, AcariError> {
+    self.create_tracker(entry_id)
+  }
+
+  /// Stop tracking, time entries already known to the caller are used to resolve the tracker instead of fetching them again.
+  fn delete_tracker_with_entries(&self, entry_id: &TimeEntryId, _known_entries: &[TimeEntry]) -> Result<Tracker, AcariError> {
+    self.delete_tracker(entry_id)
+  }
 }
 
 #[macro_export]
@@ -1,32 +1,41 @@
+use crate::client_builder::{ClientBuilder, USER_AGENT};
 use crate::error::AcariError;
 use crate::mite_model::{date_span_query_param, MiteEntity, MiteTracker};
-use crate::model::{Account, Customer, Minutes, Project, ProjectId, Service, ServiceId, TimeEntry, TimeEntryId, Tracker, User};
+use crate::model::{Account, Capabilities, Customer, Minutes, Project, ProjectId, Service, ServiceId, TimeEntry, TimeEntryId, Tracker, User};
 use crate::query::{DateSpan, Day};
+use crate::secret::Secret;
 use crate::Client;
 use serde::de::DeserializeOwned;
 use serde::ser::Serialize;
 use serde_json::json;
+use std::collections::HashMap;
 use url::Url;
 
 use reqwest::{blocking, header, Method, StatusCode};
 
-const USER_AGENT: &str = "acari-lib (https://github.com/untoldwind/acari)";
-
 #[derive(Debug)]
 pub struct MiteClient {
   base_url: Url,
+  token: Secret,
   client: blocking::Client,
+  user_agent: String,
 }
 
 impl MiteClient {
-  pub fn new(domain: &str, token: &str) -> Result<MiteClient, AcariError> {
-    Ok(Self::new_form_url(format!("https://{}@{}", token, domain).parse()?))
+  pub fn new(domain: &str, token: &Secret) -> Result<MiteClient, AcariError> {
+    ClientBuilder::new().build_mite(domain, token)
+  }
+
+  pub fn new_form_url(base_url: Url, token: Secret) -> MiteClient {
+    Self::new_with_client(base_url, token, blocking::Client::new(), USER_AGENT.to_string())
   }
 
-  pub fn new_form_url(base_url: Url) -> MiteClient {
+  pub(crate) fn new_with_client(base_url: Url, token: Secret, client: blocking::Client, user_agent: String) -> MiteClient {
     MiteClient {
       base_url,
-      client: blocking::Client::new(),
+      token,
+      client,
+      user_agent,
     }
   }
 
@@ -35,9 +44,9 @@ impl MiteClient {
       self
         .client
         .request(method, self.base_url.join(uri)?.as_str())
-        .header(header::USER_AGENT, USER_AGENT)
+        .header(header::USER_AGENT, self.user_agent.as_str())
         .header(header::HOST, self.base_url.host_str().unwrap_or(""))
-        .header("X-MiteApiKey", self.base_url.username()),
+        .header("X-MiteApiKey", self.token.expose()),
     )
   }
 
@@ -85,25 +94,36 @@ impl MiteClient {
     }
   }
 
-  fn get_time_entry(&self, entry_id: &TimeEntryId) -> Result<TimeEntry, AcariError> {
-    match self.request(Method::GET, &format!("/time_entries/{}.json", entry_id))? {
-      MiteEntity::TimeEntry(time_entry) => Ok(time_entry.into()),
-      response => Err(AcariError::Mite(400, format!("Unexpected response: {:?}", response))),
+  fn known_or_get_time_entry(&self, entry_id: &TimeEntryId, known_entries: &[TimeEntry]) -> Result<TimeEntry, AcariError> {
+    match known_entries.iter().find(|e| e.id == *entry_id) {
+      Some(entry) => Ok(entry.clone()),
+      None => self.get_time_entry(entry_id),
     }
   }
 
-  fn convert_tracker(&self, tracker: MiteTracker) -> Result<Tracker, AcariError> {
+  fn convert_tracker(&self, tracker: MiteTracker, known_entries: &[TimeEntry]) -> Result<Tracker, AcariError> {
     let tracking_time_entry = tracker
       .tracking_time_entry
       .as_ref()
       .map(|e| {
-        self.get_time_entry(&e.id).map(|mut entry| {
+        self.known_or_get_time_entry(&e.id, known_entries).map(|mut entry| {
           entry.minutes = e.minutes;
           entry
         })
       })
       .transpose()?;
-    let stopped_time_entry = tracker.stopped_time_entry.as_ref().map(|e| self.get_time_entry(&e.id)).transpose()?;
+    let stopped_time_entry = tracker
+      .stopped_time_entry
+      .as_ref()
+      .map(|e| match known_entries.iter().find(|known| known.id == e.id) {
+        // Known entries might have been fetched before the tracker was stopped
+        Some(entry) => Ok(TimeEntry {
+          minutes: e.minutes,
+          ..entry.clone()
+        }),
+        None => self.get_time_entry(&e.id),
+      })
+      .transpose()?;
 
     Ok(Tracker {
       since: tracker.tracking_time_entry.and_then(|e| e.since),
@@ -118,6 +138,17 @@ impl Client for MiteClient {
     self.base_url.host_str().unwrap_or("").to_owned()
   }
 
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      tracker: true,
+      notes: true,
+      billable: true,
+      multiple_entries_per_day: true,
+      locking: true,
+      team_queries: true,
+    }
+  }
+
   fn get_account(&self) -> Result<Account, AcariError> {
     match self.request(Method::GET, "/account.json")? {
       MiteEntity::Account(account) => Ok(account.into()),
@@ -171,6 +202,22 @@ impl Client for MiteClient {
     )
   }
 
+  fn get_services_for_projects(&self, project_ids: &[ProjectId]) -> Result<HashMap<ProjectId, Vec<Service>>, AcariError> {
+    // mite services are not project specific
+    let services = self.get_services(&ProjectId::default())?;
+
+    Ok(project_ids.iter().map(|project_id| (project_id.clone(), services.clone())).collect())
+  }
+
+  fn get_time_entry(&self, entry_id: &TimeEntryId) -> Result<TimeEntry, AcariError> {
+    match self.request(Method::GET, &format!("/time_entries/{}.json", entry_id)) {
+      Ok(MiteEntity::TimeEntry(time_entry)) => Ok(time_entry.into()),
+      Ok(response) => Err(AcariError::Mite(400, format!("Unexpected response: {:?}", response))),
+      Err(AcariError::Mite(404, _)) => Err(AcariError::NotFound(format!("No time entry with id {}", entry_id))),
+      Err(err) => Err(err),
+    }
+  }
+
   fn get_time_entries(&self, date_span: DateSpan) -> Result<Vec<TimeEntry>, AcariError> {
     Ok(
       self
@@ -184,6 +231,16 @@ impl Client for MiteClient {
     )
   }
 
+  fn get_time_entries_with_tracker(&self, date_span: DateSpan) -> Result<(Vec<TimeEntry>, Tracker), AcariError> {
+    let time_entries = self.get_time_entries(date_span)?;
+    let tracker = match self.request(Method::GET, "/tracker.json")? {
+      MiteEntity::Tracker(tracker) => self.convert_tracker(tracker, &time_entries)?,
+      response => return Err(AcariError::Mite(400, format!("Unexpected response: {:?}", response))),
+    };
+
+    Ok((time_entries, tracker))
+  }
+
   fn create_time_entry(
     &self,
     day: Day,
@@ -229,21 +286,29 @@ impl Client for MiteClient {
 
   fn get_tracker(&self) -> Result<Tracker, AcariError> {
     match self.request(Method::GET, "/tracker.json")? {
-      MiteEntity::Tracker(tracker) => Ok(self.convert_tracker(tracker)?),
+      MiteEntity::Tracker(tracker) => Ok(self.convert_tracker(tracker, &[])?),
       response => Err(AcariError::Mite(400, format!("Unexpected response: {:?}", response))),
     }
   }
 
   fn create_tracker(&self, entry_id: &TimeEntryId) -> Result<Tracker, AcariError> {
+    self.create_tracker_with_entries(entry_id, &[])
+  }
+
+  fn delete_tracker(&self, entry_id: &TimeEntryId) -> Result<Tracker, AcariError> {
+    self.delete_tracker_with_entries(entry_id, &[])
+  }
+
+  fn create_tracker_with_entries(&self, entry_id: &TimeEntryId, known_entries: &[TimeEntry]) -> Result<Tracker, AcariError> {
     match self.request(Method::PATCH, &format!("/tracker/{}.json", entry_id))? {
-      MiteEntity::Tracker(tracker) => Ok(self.convert_tracker(tracker)?),
+      MiteEntity::Tracker(tracker) => Ok(self.convert_tracker(tracker, known_entries)?),
       response => Err(AcariError::Mite(400, format!("Unexpected response: {:?}", response))),
     }
   }
 
-  fn delete_tracker(&self, entry_id: &TimeEntryId) -> Result<Tracker, AcariError> {
+  fn delete_tracker_with_entries(&self, entry_id: &TimeEntryId, known_entries: &[TimeEntry]) -> Result<Tracker, AcariError> {
     match self.request(Method::DELETE, &format!("/tracker/{}.json", entry_id))? {
-      MiteEntity::Tracker(tracker) => Ok(self.convert_tracker(tracker)?),
+      MiteEntity::Tracker(tracker) => Ok(self.convert_tracker(tracker, known_entries)?),
       response => Err(AcariError::Mite(400, format!("Unexpected response: {:?}", response))),
     }
   }
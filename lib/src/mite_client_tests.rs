@@ -5,8 +5,8 @@ use pretty_assertions::assert_eq;
 use serde_json::json;
 
 use super::{
-  Account, AccountId, Client, Customer, CustomerId, DateSpan, Day, Minutes, MiteClient, Project, ProjectId, Service, ServiceId, TimeEntry, TimeEntryId,
-  Tracker, User, UserId,
+  AcariError, Account, AccountId, Client, Customer, CustomerId, DateSpan, Day, Minutes, MiteClient, Project, ProjectId, Secret, Service, ServiceId, TimeEntry,
+  TimeEntryId, Tracker, User, UserId,
 };
 
 const CONSUMER: &str = "acari-lib";
@@ -31,9 +31,7 @@ fn test_get_account() -> Result<(), Box<dyn std::error::Error>> {
     })
     .build();
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let account = client.get_account()?;
 
@@ -73,9 +71,7 @@ fn test_get_myself() -> Result<(), Box<dyn std::error::Error>> {
     })
     .build();
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let user = client.get_myself()?;
 
@@ -128,9 +124,7 @@ fn test_get_customers() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let customers = client.get_customers()?;
 
@@ -185,9 +179,7 @@ fn test_get_projects() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let projects = client.get_projects()?;
 
@@ -230,9 +222,7 @@ fn test_get_services() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let services = client.get_services(&ProjectId::Num(0))?;
 
@@ -244,6 +234,9 @@ fn test_get_services() -> Result<(), Box<dyn std::error::Error>> {
       note: "".to_string(),
       archived: false,
       billable: true,
+      section: None,
+      estimate: None,
+      time_spent: None,
       created_at: Utc.ymd(2009, 12, 13).and_hms(11, 12, 00),
     },
     services[0]
@@ -308,9 +301,7 @@ fn test_query_entries() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let entries = client.get_time_entries(DateSpan::Day(Day::Date(NaiveDate::from_ymd(2015, 10, 16))))?;
 
@@ -320,6 +311,95 @@ fn test_query_entries() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[test]
+fn test_get_entry() -> Result<(), Box<dyn std::error::Error>> {
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("get time entry", |i| {
+      i.given("User with API token");
+      i.request
+        .get()
+        .path("/time_entries/36159117.json")
+        .header("X-MiteApiKey", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!({
+        "time_entry": {
+           "id": 36159117,
+           "minutes": 15,
+           "date_at": "2015-10-16",
+           "note": "Feedback einarbeiten",
+           "billable": true,
+           "locked": true,
+           "revenue": null,
+           "hourly_rate": 0,
+           "user_id": 211,
+           "user_name": "Fridolin Frei",
+           "project_id": 88309,
+           "project_name": "API v2",
+           "customer_id": 3213,
+           "customer_name": "König",
+           "service_id": 12984,
+           "service_name": "Entwurf",
+           "created_at": "2015-10-16T12:19:00+02:00",
+           "updated_at": "2015-10-16T12:39:00+02:00"
+        }
+      }));
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  let entry = client.get_time_entry(&TimeEntryId::Num(36159117))?;
+
+  assert_eq!(
+    TimeEntry {
+      id: TimeEntryId::Num(36159117),
+      minutes: Minutes(15),
+      date_at: NaiveDate::from_ymd(2015, 10, 16),
+      note: "Feedback einarbeiten".to_string(),
+      locked: true,
+      billable: true,
+      user_id: UserId::Num(211),
+      user_name: "Fridolin Frei".to_string(),
+      customer_id: CustomerId::Num(3213),
+      customer_name: "König".to_string(),
+      service_id: ServiceId::Num(12984),
+      service_name: "Entwurf".to_string(),
+      project_id: ProjectId::Num(88309),
+      project_name: "API v2".to_string(),
+      created_at: Utc.ymd(2015, 10, 16).and_hms(10, 19, 00),
+    },
+    entry
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_get_entry_not_found() -> Result<(), Box<dyn std::error::Error>> {
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("get missing time entry", |i| {
+      i.given("User with API token");
+      i.request
+        .get()
+        .path("/time_entries/4711.json")
+        .header("X-MiteApiKey", term!("[0-9a-f]+", "12345678"));
+      i.response.not_found().json_utf8().json_body(json!({
+        "error": "Record not found"
+      }));
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  match client.get_time_entry(&TimeEntryId::Num(4711)) {
+    Err(AcariError::NotFound(_)) => (),
+    result => panic!("Expected not found, got: {:?}", result),
+  }
+
+  Ok(())
+}
+
 #[test]
 fn test_create_entry() -> Result<(), Box<dyn std::error::Error>> {
   let pact = PactBuilder::new(CONSUMER, PROVIDER)
@@ -364,9 +444,7 @@ fn test_create_entry() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let entry = client.create_time_entry(
     Day::Date(NaiveDate::from_ymd(2015, 9, 15)),
@@ -414,9 +492,7 @@ fn test_delete_entry() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   client.delete_time_entry(&TimeEntryId::Num(52324))?;
 
@@ -443,9 +519,7 @@ fn test_update_entry() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   client.update_time_entry(&TimeEntryId::Num(52324), Minutes(120), None)?;
 
@@ -519,9 +593,7 @@ fn test_get_tracker() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let tracker = client.get_tracker()?;
 
@@ -537,6 +609,95 @@ fn test_get_tracker() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[test]
+fn test_get_time_entries_with_tracker() -> Result<(), Box<dyn std::error::Error>> {
+  let time_entry_json = json!({
+    "time_entry": {
+       "id": 36135321,
+       "minutes": 15,
+       "date_at": "2015-10-16",
+       "note": "Feedback einarbeiten",
+       "billable": true,
+       "locked": false,
+       "revenue": null,
+       "hourly_rate": 0,
+       "user_id": 211,
+       "user_name": "Fridolin Frei",
+       "project_id": 88309,
+       "project_name": "API v2",
+       "customer_id": 3213,
+       "customer_name": "König",
+       "service_id": 12984,
+       "service_name": "Entwurf",
+       "created_at": "2015-10-16T12:19:00+02:00",
+       "updated_at": "2015-10-16T12:39:00+02:00"
+    }
+  });
+  let expected = TimeEntry {
+    id: TimeEntryId::Num(36135321),
+    minutes: Minutes(15),
+    date_at: NaiveDate::from_ymd(2015, 10, 16),
+    note: "Feedback einarbeiten".to_string(),
+    locked: false,
+    billable: true,
+    user_id: UserId::Num(211),
+    user_name: "Fridolin Frei".to_string(),
+    customer_id: CustomerId::Num(3213),
+    customer_name: "König".to_string(),
+    service_id: ServiceId::Num(12984),
+    service_name: "Entwurf".to_string(),
+    project_id: ProjectId::Num(88309),
+    project_name: "API v2".to_string(),
+    created_at: Utc.ymd(2015, 10, 16).and_hms(10, 19, 00),
+  };
+
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("query time entries for tracker", |i| {
+      i.given("User with API token");
+      i.request
+        .get()
+        .path("/time_entries.json")
+        .query_param("at", "2015-10-16")
+        .query_param("user", "current")
+        .header("X-MiteApiKey", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!([time_entry_json]));
+    })
+    .interaction("get tracker of queried entry", |i| {
+      i.given("User with API token");
+      i.request.get().path("/tracker.json").header("X-MiteApiKey", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!({
+        "tracker": {
+          "tracking_time_entry": {
+            "id": 36135321,
+            "minutes": 247,
+            "since": "2015-10-16T17:05:04+02:00"
+          }
+        }
+      }));
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  let (entries, tracker) = client.get_time_entries_with_tracker(DateSpan::Day(Day::Date(NaiveDate::from_ymd(2015, 10, 16))))?;
+
+  assert_eq!(entries, vec![expected.clone()]);
+  assert_eq!(
+    Tracker {
+      since: Some(Utc.ymd(2015, 10, 16).and_hms(15, 05, 04)),
+      tracking_time_entry: Some(TimeEntry {
+        minutes: Minutes(247),
+        ..expected
+      }),
+      stopped_time_entry: None,
+    },
+    tracker
+  );
+
+  Ok(())
+}
+
 #[test]
 fn test_create_tracker() -> Result<(), Box<dyn std::error::Error>> {
   let time_entry_json = json!({
@@ -619,9 +780,7 @@ fn test_create_tracker() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let tracker = client.create_tracker(&TimeEntryId::Num(36135322))?;
 
@@ -706,9 +865,7 @@ fn test_delete_tracker() -> Result<(), Box<dyn std::error::Error>> {
     .build();
 
   let server = pact.start_mock_server();
-  let mut url = server.url().clone();
-  url.set_username("12345678").unwrap();
-  let client = MiteClient::new_form_url(url);
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
 
   let tracker = client.delete_tracker(&TimeEntryId::Num(36135322))?;
 
@@ -723,3 +880,59 @@ fn test_delete_tracker() -> Result<(), Box<dyn std::error::Error>> {
 
   Ok(())
 }
+
+#[test]
+fn test_delete_tracker_with_known_entries() -> Result<(), Box<dyn std::error::Error>> {
+  let known = TimeEntry {
+    id: TimeEntryId::Num(36135322),
+    minutes: Minutes(15),
+    date_at: NaiveDate::from_ymd(2015, 10, 16),
+    note: "Feedback einarbeiten".to_string(),
+    locked: false,
+    billable: true,
+    user_id: UserId::Num(211),
+    user_name: "Fridolin Frei".to_string(),
+    customer_id: CustomerId::Num(3213),
+    customer_name: "König".to_string(),
+    service_id: ServiceId::Num(12984),
+    service_name: "Entwurf".to_string(),
+    project_id: ProjectId::Num(88309),
+    project_name: "API v2".to_string(),
+    created_at: Utc.ymd(2015, 10, 16).and_hms(10, 19, 00),
+  };
+
+  // No interaction to get the time entry, the known one has to be used
+  let pact = PactBuilder::new(CONSUMER, PROVIDER)
+    .interaction("delete tracker of known entry", |i| {
+      i.given("User with API token");
+      i.request
+        .delete()
+        .path("/tracker/36135322.json")
+        .header("X-MiteApiKey", term!("[0-9a-f]+", "12345678"));
+      i.response.ok().json_utf8().json_body(json!({
+        "tracker": {
+         "stopped_time_entry": {
+            "id": 36135322,
+            "minutes": 19
+          }
+        }
+      }));
+    })
+    .build();
+
+  let server = pact.start_mock_server();
+  let client = MiteClient::new_form_url(server.url().clone(), Secret::new("12345678"));
+
+  let tracker = client.delete_tracker_with_entries(&TimeEntryId::Num(36135322), &[known.clone()])?;
+
+  assert_eq!(
+    Tracker {
+      since: None,
+      tracking_time_entry: None,
+      stopped_time_entry: Some(TimeEntry { minutes: Minutes(19), ..known }),
+    },
+    tracker
+  );
+
+  Ok(())
+}
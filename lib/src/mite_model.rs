@@ -127,6 +127,9 @@ impl From<MiteService> for Service {
       note: f.note,
       billable: f.billable,
       archived: f.archived,
+      section: None,
+      estimate: None,
+      time_spent: None,
       created_at: f.created_at,
     }
   }
@@ -0,0 +1,497 @@
+use crate::error::AcariError;
+use crate::model::{Account, AccountId, Capabilities, Customer, Minutes, Project, Service, TimeEntry, Tracker, User, UserId};
+use crate::model::{ProjectId, ServiceId, TimeEntryId};
+use crate::query::{DateSpan, Day};
+use crate::Client;
+use chrono::{NaiveDate, Utc};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Operations of the `Client` trait, used to inject errors into a `MockClient`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MockOperation {
+  GetAccount,
+  GetMyself,
+  GetCustomers,
+  GetProjects,
+  GetServices,
+  GetTimeEntry,
+  GetTimeEntries,
+  CreateTimeEntry,
+  UpdateTimeEntry,
+  DeleteTimeEntry,
+  GetTracker,
+  CreateTracker,
+  DeleteTracker,
+}
+
+/// Modifying calls recorded by a `MockClient`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mutation {
+  CreateTimeEntry {
+    date: NaiveDate,
+    project_id: ProjectId,
+    service_id: ServiceId,
+    minutes: Minutes,
+    note: Option<String>,
+  },
+  UpdateTimeEntry {
+    entry_id: TimeEntryId,
+    minutes: Minutes,
+    note: Option<String>,
+  },
+  DeleteTimeEntry {
+    entry_id: TimeEntryId,
+  },
+  CreateTracker {
+    entry_id: TimeEntryId,
+  },
+  DeleteTracker {
+    entry_id: TimeEntryId,
+  },
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+  time_entries: Vec<TimeEntry>,
+  tracker: Option<Tracker>,
+  mutations: Vec<Mutation>,
+  errors: HashMap<MockOperation, AcariError>,
+  next_entry_id: u64,
+}
+
+/// In-memory implementation of `Client` for tests.
+///
+/// All data is seeded via the `with_*` builder methods. Modifying calls change the in-memory state
+/// and are recorded as `Mutation`s. Errors injected via `with_error` are returned once by the next
+/// call of the operation.
+#[derive(Debug)]
+pub struct MockClient {
+  domain: String,
+  capabilities: Capabilities,
+  account: Account,
+  user: User,
+  customers: Vec<Customer>,
+  projects: Vec<Project>,
+  services: Vec<(ProjectId, Service)>,
+  state: Mutex<MockState>,
+}
+
+impl Default for MockClient {
+  fn default() -> Self {
+    MockClient::new()
+  }
+}
+
+impl MockClient {
+  pub fn new() -> MockClient {
+    MockClient {
+      domain: "mock.local".to_string(),
+      capabilities: Capabilities {
+        tracker: true,
+        notes: true,
+        billable: true,
+        multiple_entries_per_day: true,
+        locking: true,
+        team_queries: false,
+      },
+      account: Account {
+        id: AccountId::Num(1),
+        name: "mock".to_string(),
+        title: "Mock".to_string(),
+        currency: "EUR".to_string(),
+        created_at: Utc::now(),
+      },
+      user: User {
+        id: UserId::Num(1),
+        name: "Mock User".to_string(),
+        email: "mock@mock.local".to_string(),
+        note: "".to_string(),
+        role: "owner".to_string(),
+        language: "en".to_string(),
+        archived: false,
+        created_at: Utc::now(),
+      },
+      customers: vec![],
+      projects: vec![],
+      services: vec![],
+      state: Mutex::new(MockState {
+        next_entry_id: 1,
+        ..Default::default()
+      }),
+    }
+  }
+
+  pub fn with_domain(mut self, domain: &str) -> Self {
+    self.domain = domain.to_string();
+    self
+  }
+
+  pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+    self.capabilities = capabilities;
+    self
+  }
+
+  pub fn with_account(mut self, account: Account) -> Self {
+    self.account = account;
+    self
+  }
+
+  pub fn with_user(mut self, user: User) -> Self {
+    self.user = user;
+    self
+  }
+
+  pub fn with_customer(mut self, customer: Customer) -> Self {
+    self.customers.push(customer);
+    self
+  }
+
+  pub fn with_project(mut self, project: Project) -> Self {
+    self.projects.push(project);
+    self
+  }
+
+  pub fn with_service(mut self, project_id: &ProjectId, service: Service) -> Self {
+    self.services.push((project_id.clone(), service));
+    self
+  }
+
+  pub fn with_time_entry(self, time_entry: TimeEntry) -> Self {
+    {
+      let mut state = self.lock_state();
+      if let TimeEntryId::Num(id) = time_entry.id {
+        state.next_entry_id = state.next_entry_id.max(id + 1);
+      }
+      state.time_entries.push(time_entry);
+    }
+    self
+  }
+
+  pub fn with_tracker(self, tracker: Tracker) -> Self {
+    self.lock_state().tracker = Some(tracker);
+    self
+  }
+
+  pub fn with_error(self, operation: MockOperation, error: AcariError) -> Self {
+    self.lock_state().errors.insert(operation, error);
+    self
+  }
+
+  /// All modifying calls in the order they were made
+  pub fn mutations(&self) -> Vec<Mutation> {
+    self.lock_state().mutations.clone()
+  }
+
+  /// Current state of all time entries
+  pub fn time_entries(&self) -> Vec<TimeEntry> {
+    self.lock_state().time_entries.clone()
+  }
+
+  fn lock_state(&self) -> MutexGuard<'_, MockState> {
+    self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+
+  fn check_error(&self, operation: MockOperation) -> Result<(), AcariError> {
+    match self.lock_state().errors.remove(&operation) {
+      Some(error) => Err(error),
+      None => Ok(()),
+    }
+  }
+
+  fn not_found(entry_id: &TimeEntryId) -> AcariError {
+    AcariError::NotFound(format!("No time entry with id {}", entry_id))
+  }
+}
+
+impl Client for MockClient {
+  fn get_domain(&self) -> String {
+    self.domain.clone()
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    self.capabilities
+  }
+
+  fn get_account(&self) -> Result<Account, AcariError> {
+    self.check_error(MockOperation::GetAccount)?;
+    Ok(self.account.clone())
+  }
+
+  fn get_myself(&self) -> Result<User, AcariError> {
+    self.check_error(MockOperation::GetMyself)?;
+    Ok(self.user.clone())
+  }
+
+  fn get_customers(&self) -> Result<Vec<Customer>, AcariError> {
+    self.check_error(MockOperation::GetCustomers)?;
+    Ok(self.customers.clone())
+  }
+
+  fn get_projects(&self) -> Result<Vec<Project>, AcariError> {
+    self.check_error(MockOperation::GetProjects)?;
+    Ok(self.projects.clone())
+  }
+
+  fn get_services(&self, project_id: &ProjectId) -> Result<Vec<Service>, AcariError> {
+    self.check_error(MockOperation::GetServices)?;
+    Ok(self.services.iter().filter(|(p, _)| p == project_id).map(|(_, s)| s.clone()).collect())
+  }
+
+  fn get_time_entry(&self, entry_id: &TimeEntryId) -> Result<TimeEntry, AcariError> {
+    self.check_error(MockOperation::GetTimeEntry)?;
+    self
+      .lock_state()
+      .time_entries
+      .iter()
+      .find(|e| e.id == *entry_id)
+      .cloned()
+      .ok_or_else(|| Self::not_found(entry_id))
+  }
+
+  fn get_time_entries(&self, date_span: DateSpan) -> Result<Vec<TimeEntry>, AcariError> {
+    self.check_error(MockOperation::GetTimeEntries)?;
+    Ok(
+      self
+        .lock_state()
+        .time_entries
+        .iter()
+        .filter(|e| date_span.contains(&e.date_at))
+        .cloned()
+        .collect(),
+    )
+  }
+
+  fn create_time_entry(
+    &self,
+    day: Day,
+    project_id: &ProjectId,
+    service_id: &ServiceId,
+    minutes: Minutes,
+    note: Option<String>,
+  ) -> Result<TimeEntry, AcariError> {
+    self.check_error(MockOperation::CreateTimeEntry)?;
+
+    let project = self
+      .projects
+      .iter()
+      .find(|p| p.id == *project_id)
+      .ok_or_else(|| AcariError::NotFound(format!("No project with id {}", project_id)))?;
+    let service = self
+      .services
+      .iter()
+      .find(|(p, s)| p == project_id && s.id == *service_id)
+      .map(|(_, s)| s)
+      .ok_or_else(|| AcariError::NotFound(format!("No service with id {}", service_id)))?;
+    let mut state = self.lock_state();
+    let entry = TimeEntry {
+      id: TimeEntryId::Num(state.next_entry_id),
+      date_at: day.as_date(),
+      minutes,
+      customer_id: project.customer_id.clone(),
+      customer_name: project.customer_name.clone(),
+      project_id: project.id.clone(),
+      project_name: project.name.clone(),
+      service_id: service.id.clone(),
+      service_name: service.name.clone(),
+      user_id: self.user.id.clone(),
+      user_name: self.user.name.clone(),
+      note: note.clone().unwrap_or_default(),
+      billable: service.billable,
+      locked: false,
+      created_at: Utc::now(),
+    };
+
+    state.next_entry_id += 1;
+    state.time_entries.push(entry.clone());
+    state.mutations.push(Mutation::CreateTimeEntry {
+      date: entry.date_at,
+      project_id: project_id.clone(),
+      service_id: service_id.clone(),
+      minutes,
+      note,
+    });
+
+    Ok(entry)
+  }
+
+  fn update_time_entry(&self, entry_id: &TimeEntryId, minutes: Minutes, note: Option<String>) -> Result<(), AcariError> {
+    self.check_error(MockOperation::UpdateTimeEntry)?;
+
+    let mut state = self.lock_state();
+    let entry = state
+      .time_entries
+      .iter_mut()
+      .find(|e| e.id == *entry_id)
+      .ok_or_else(|| Self::not_found(entry_id))?;
+
+    entry.minutes = minutes;
+    entry.note = note.clone().unwrap_or_default();
+    state.mutations.push(Mutation::UpdateTimeEntry {
+      entry_id: entry_id.clone(),
+      minutes,
+      note,
+    });
+
+    Ok(())
+  }
+
+  fn delete_time_entry(&self, entry_id: &TimeEntryId) -> Result<(), AcariError> {
+    self.check_error(MockOperation::DeleteTimeEntry)?;
+
+    let mut state = self.lock_state();
+    let count = state.time_entries.len();
+
+    state.time_entries.retain(|e| e.id != *entry_id);
+    if state.time_entries.len() == count {
+      return Err(Self::not_found(entry_id));
+    }
+    state.mutations.push(Mutation::DeleteTimeEntry { entry_id: entry_id.clone() });
+
+    Ok(())
+  }
+
+  fn get_tracker(&self) -> Result<Tracker, AcariError> {
+    self.check_error(MockOperation::GetTracker)?;
+    Ok(self.lock_state().tracker.clone().unwrap_or(Tracker {
+      since: None,
+      tracking_time_entry: None,
+      stopped_time_entry: None,
+    }))
+  }
+
+  fn create_tracker(&self, entry_id: &TimeEntryId) -> Result<Tracker, AcariError> {
+    self.check_error(MockOperation::CreateTracker)?;
+
+    let mut state = self.lock_state();
+    let entry = state
+      .time_entries
+      .iter()
+      .find(|e| e.id == *entry_id)
+      .cloned()
+      .ok_or_else(|| Self::not_found(entry_id))?;
+    let tracker = Tracker {
+      since: Some(Utc::now()),
+      tracking_time_entry: Some(entry),
+      stopped_time_entry: state.tracker.take().and_then(|t| t.tracking_time_entry),
+    };
+
+    state.tracker = Some(tracker.clone());
+    state.mutations.push(Mutation::CreateTracker { entry_id: entry_id.clone() });
+
+    Ok(tracker)
+  }
+
+  fn delete_tracker(&self, entry_id: &TimeEntryId) -> Result<Tracker, AcariError> {
+    self.check_error(MockOperation::DeleteTracker)?;
+
+    let mut state = self.lock_state();
+    let tracker = Tracker {
+      since: None,
+      tracking_time_entry: None,
+      stopped_time_entry: state.tracker.take().and_then(|t| t.tracking_time_entry),
+    };
+
+    state.tracker = Some(tracker.clone());
+    state.mutations.push(Mutation::DeleteTracker { entry_id: entry_id.clone() });
+
+    Ok(tracker)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::model::CustomerId;
+  use pretty_assertions::assert_eq;
+
+  fn mock_client() -> MockClient {
+    MockClient::new()
+      .with_project(Project {
+        id: ProjectId::Num(10),
+        name: "Website".to_string(),
+        customer_id: CustomerId::Num(1),
+        customer_name: "ACME".to_string(),
+        note: "".to_string(),
+        archived: false,
+        created_at: Utc::now(),
+      })
+      .with_service(
+        &ProjectId::Num(10),
+        Service {
+          id: ServiceId::Num(100),
+          name: "Development".to_string(),
+          note: "".to_string(),
+          billable: true,
+          archived: false,
+          section: None,
+          estimate: None,
+          time_spent: None,
+          created_at: Utc::now(),
+        },
+      )
+  }
+
+  #[test]
+  fn test_create_update_delete() -> Result<(), Box<dyn std::error::Error>> {
+    let client = mock_client();
+    let date = NaiveDate::from_ymd(2021, 2, 1);
+
+    let entry = client.create_time_entry(Day::Date(date), &ProjectId::Num(10), &ServiceId::Num(100), Minutes(30), None)?;
+
+    assert_eq!(entry.customer_name, "ACME");
+    assert_eq!(client.get_time_entries(date.into())?, vec![entry.clone()]);
+
+    client.update_time_entry(&entry.id, Minutes(45), Some("note".to_string()))?;
+    assert_eq!(client.get_time_entry(&entry.id)?.minutes, Minutes(45));
+
+    client.delete_time_entry(&entry.id)?;
+    assert!(client.time_entries().is_empty());
+    assert_eq!(
+      client.mutations(),
+      vec![
+        Mutation::CreateTimeEntry {
+          date,
+          project_id: ProjectId::Num(10),
+          service_id: ServiceId::Num(100),
+          minutes: Minutes(30),
+          note: None,
+        },
+        Mutation::UpdateTimeEntry {
+          entry_id: entry.id.clone(),
+          minutes: Minutes(45),
+          note: Some("note".to_string()),
+        },
+        Mutation::DeleteTimeEntry { entry_id: entry.id },
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_tracker() -> Result<(), Box<dyn std::error::Error>> {
+    let client = mock_client();
+    let entry = client.create_time_entry(Day::Today, &ProjectId::Num(10), &ServiceId::Num(100), Minutes(0), None)?;
+
+    let tracker = client.create_tracker(&entry.id)?;
+    assert_eq!(tracker.tracking_time_entry.as_ref(), Some(&entry));
+    assert_eq!(client.get_tracker()?, tracker);
+
+    let tracker = client.delete_tracker(&entry.id)?;
+    assert_eq!(tracker.tracking_time_entry, None);
+    assert_eq!(tracker.stopped_time_entry, Some(entry));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_injected_error() -> Result<(), Box<dyn std::error::Error>> {
+    let client = mock_client().with_error(MockOperation::GetProjects, AcariError::Mite(500, "Internal".to_string()));
+
+    assert!(matches!(client.get_projects(), Err(AcariError::Mite(500, _))));
+    assert_eq!(client.get_projects()?.len(), 1);
+
+    Ok(())
+  }
+}
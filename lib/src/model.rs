@@ -109,7 +109,7 @@ id_wrapper!(ProjectId);
 id_wrapper!(ServiceId);
 id_wrapper!(TimeEntryId);
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct Account {
   pub id: AccountId,
   pub name: String,
@@ -118,7 +118,7 @@ pub struct Account {
   pub created_at: DateTime<Utc>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct User {
   pub id: UserId,
   pub name: String,
@@ -130,7 +130,7 @@ pub struct User {
   pub created_at: DateTime<Utc>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct Customer {
   pub id: CustomerId,
   pub name: String,
@@ -139,7 +139,7 @@ pub struct Customer {
   pub created_at: DateTime<Utc>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct Project {
   pub id: ProjectId,
   pub name: String,
@@ -150,13 +150,19 @@ pub struct Project {
   pub created_at: DateTime<Utc>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct Service {
   pub id: ServiceId,
   pub name: String,
   pub note: String,
   pub billable: bool,
   pub archived: bool,
+  #[serde(default)]
+  pub section: Option<String>,
+  #[serde(default)]
+  pub estimate: Option<Minutes>,
+  #[serde(default)]
+  pub time_spent: Option<Minutes>,
   pub created_at: DateTime<Utc>,
 }
 
@@ -186,6 +192,16 @@ pub struct Tracker {
   pub stopped_time_entry: Option<TimeEntry>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+  pub tracker: bool,
+  pub notes: bool,
+  pub billable: bool,
+  pub multiple_entries_per_day: bool,
+  pub locking: bool,
+  pub team_queries: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
 pub struct Minutes(pub u32);
 
@@ -0,0 +1,69 @@
+use crate::error::AcariError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+pub const MAX_WORKERS: usize = 4;
+
+/// Map all items with a bounded pool of worker threads, results are in the order of the items.
+pub fn map_parallel<T, R, F>(items: &[T], f: F) -> Result<Vec<R>, AcariError>
+where
+  T: Sync,
+  R: Send,
+  F: Fn(&T) -> Result<R, AcariError> + Sync,
+{
+  let next = AtomicUsize::new(0);
+  let workers = MAX_WORKERS.min(items.len());
+
+  let mut results: Vec<(usize, Result<R, AcariError>)> = thread::scope(|scope| {
+    let handles: Vec<_> = (0..workers)
+      .map(|_| {
+        scope.spawn(|| {
+          let mut worker_results = Vec::new();
+          loop {
+            let idx = next.fetch_add(1, Ordering::SeqCst);
+            match items.get(idx) {
+              Some(item) => worker_results.push((idx, f(item))),
+              None => break,
+            }
+          }
+          worker_results
+        })
+      })
+      .collect();
+
+    handles.into_iter().flat_map(|handle| handle.join().unwrap_or_default()).collect()
+  });
+
+  if results.len() != items.len() {
+    return Err(AcariError::InternalError("Worker thread panicked".to_string()));
+  }
+  results.sort_by_key(|(idx, _)| *idx);
+
+  results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_map_parallel() -> Result<(), Box<dyn std::error::Error>> {
+    let items: Vec<u32> = (0..20).collect();
+
+    assert_eq!(map_parallel(&items, |i| Ok(i * 2))?, items.iter().map(|i| i * 2).collect::<Vec<u32>>());
+    assert_eq!(map_parallel(&Vec::<u32>::new(), |i| Ok(*i))?, Vec::<u32>::new());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_map_parallel_error() {
+    let items: Vec<u32> = (0..20).collect();
+
+    match map_parallel(&items, |i| if *i == 13 { Err(AcariError::UserError("13".to_string())) } else { Ok(*i) }) {
+      Err(AcariError::UserError(msg)) => assert_eq!(msg, "13"),
+      result => panic!("Expected error, got: {:?}", result),
+    }
+  }
+}
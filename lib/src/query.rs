@@ -1,5 +1,5 @@
 use crate::error::AcariError;
-use chrono::{Local, NaiveDate};
+use chrono::{Datelike, Duration, Local, NaiveDate};
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -47,6 +47,45 @@ pub enum DateSpan {
   FromTo(NaiveDate, NaiveDate),
 }
 
+impl DateSpan {
+  /// First and last day (inclusive) covered by the span
+  pub fn date_range(&self) -> (NaiveDate, NaiveDate) {
+    self.date_range_from(Local::now().naive_local().date())
+  }
+
+  fn date_range_from(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+
+    match self {
+      DateSpan::ThisWeek => (monday, monday + Duration::days(6)),
+      DateSpan::LastWeek => (monday - Duration::days(7), monday - Duration::days(1)),
+      DateSpan::ThisMonth => month_range(today.year(), today.month()),
+      DateSpan::LastMonth if today.month() == 1 => month_range(today.year() - 1, 12),
+      DateSpan::LastMonth => month_range(today.year(), today.month() - 1),
+      DateSpan::Day(day) => (day.as_date(), day.as_date()),
+      DateSpan::FromTo(from, to) => (*from, *to),
+    }
+  }
+
+  pub fn contains(&self, date: &NaiveDate) -> bool {
+    let (from, to) = self.date_range();
+
+    from <= *date && *date <= to
+  }
+}
+
+fn month_range(year: i32, month: u32) -> (NaiveDate, NaiveDate) {
+  let start = NaiveDate::from_ymd_opt(year, month, 1).expect("valid month");
+  let next_start = if month == 12 {
+    NaiveDate::from_ymd_opt(year + 1, 1, 1)
+  } else {
+    NaiveDate::from_ymd_opt(year, month + 1, 1)
+  }
+  .expect("valid month");
+
+  (start, next_start - Duration::days(1))
+}
+
 impl FromStr for DateSpan {
   type Err = AcariError;
 
@@ -108,4 +147,42 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_datespan_range() {
+    let today = NaiveDate::from_ymd(2021, 1, 6);
+
+    assert_eq!(
+      DateSpan::ThisWeek.date_range_from(today),
+      (NaiveDate::from_ymd(2021, 1, 4), NaiveDate::from_ymd(2021, 1, 10))
+    );
+    assert_eq!(
+      DateSpan::LastWeek.date_range_from(today),
+      (NaiveDate::from_ymd(2020, 12, 28), NaiveDate::from_ymd(2021, 1, 3))
+    );
+    assert_eq!(
+      DateSpan::ThisMonth.date_range_from(today),
+      (NaiveDate::from_ymd(2021, 1, 1), NaiveDate::from_ymd(2021, 1, 31))
+    );
+    assert_eq!(
+      DateSpan::LastMonth.date_range_from(today),
+      (NaiveDate::from_ymd(2020, 12, 1), NaiveDate::from_ymd(2020, 12, 31))
+    );
+    assert_eq!(
+      DateSpan::Day(Day::Date(today)).date_range_from(today),
+      (NaiveDate::from_ymd(2021, 1, 6), NaiveDate::from_ymd(2021, 1, 6))
+    );
+  }
+
+  #[test]
+  fn test_datespan_range_at_year_boundary() {
+    // Monday of ISO week 1 of 2025
+    let today = NaiveDate::from_ymd_opt(2024, 12, 30).unwrap();
+
+    assert_eq!(DateSpan::ThisWeek.date_range_from(today), (today, NaiveDate::from_ymd_opt(2025, 1, 5).unwrap()));
+    assert_eq!(
+      DateSpan::LastWeek.date_range_from(today),
+      (NaiveDate::from_ymd_opt(2024, 12, 23).unwrap(), NaiveDate::from_ymd_opt(2024, 12, 29).unwrap())
+    );
+  }
 }
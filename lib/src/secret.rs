@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A secret value like an api token that never shows up in `Debug` or `Display` output.
+#[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+  pub fn new<S: Into<String>>(secret: S) -> Secret {
+    Secret(secret.into())
+  }
+
+  /// Access the actual value, e.g. to put it into a request header.
+  pub fn expose(&self) -> &str {
+    &self.0
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+}
+
+impl fmt::Debug for Secret {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Secret(***)")
+  }
+}
+
+impl fmt::Display for Secret {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "***")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_redacted() {
+    let secret = Secret::new("12345678");
+
+    assert_eq!(format!("{:?}", secret), "Secret(***)");
+    assert_eq!(format!("{}", secret), "***");
+    assert_eq!(secret.expose(), "12345678");
+  }
+}